@@ -3,6 +3,7 @@ use pinocchio::{
     instruction::{Seed, Signer},
     ProgramResult,
 };
+use pinocchio_account::PinocchioAccount;
 use pinocchio_pubkey::derive_address;
 
 use crate::state::Escrow;
@@ -48,7 +49,7 @@ pub fn process_cancel_instruction(accounts: &[AccountInfo]) -> ProgramResult {
         return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
     }
     // read the escrow pda to get amount
-    let escrow_state = Escrow::from_account_info(escrow_account)?;
+    let escrow_state = *Escrow::load(escrow_account)?;
 
     let bump = escrow_state.bump;
     let seed = [b"escrow", maker.key().as_slice(), &[bump]];
@@ -3,11 +3,10 @@ use pinocchio::{
     instruction::{Seed, Signer},
     msg,
     pubkey::log,
-    sysvars::{rent::Rent, Sysvar},
     ProgramResult,
 };
+use pinocchio_account::PinocchioAccount;
 use pinocchio_pubkey::derive_address;
-use pinocchio_system::instructions::CreateAccount;
 
 use crate::state::Escrow;
 
@@ -50,17 +49,8 @@ pub fn process_make_instruction(accounts: &[AccountInfo], data: &[u8]) -> Progra
     let seeds = Signer::from(&seed);
 
     if escrow_account.owner() != &crate::ID {
-        CreateAccount {
-            from: maker,
-            to: escrow_account,
-            lamports: Rent::get()?.minimum_balance(Escrow::LEN),
-            space: Escrow::LEN as u64,
-            owner: &crate::ID,
-        }
-        .invoke_signed(&[seeds])?;
-
         {
-            let escrow_state = Escrow::from_account_info(escrow_account)?;
+            let mut escrow_state = Escrow::init(escrow_account, maker, &[seeds])?;
 
             escrow_state.set_maker(maker.key());
             escrow_state.set_mint_a(mint_a.key());
@@ -3,6 +3,7 @@ use pinocchio::{
     instruction::{Seed, Signer},
     ProgramResult,
 };
+use pinocchio_account::PinocchioAccount;
 use pinocchio_pubkey::derive_address;
 
 use crate::state::Escrow;
@@ -45,7 +46,8 @@ pub fn process_take_instruction(accounts: &[AccountInfo]) -> ProgramResult {
     }
 
     // read escrow pda for the amounts and other config
-    let escrow_state = Escrow::from_account_info(escrow)?;
+    // copied out, the escrow is closed below
+    let escrow_state = *Escrow::load(escrow)?;
     // check the maker address is right
     if *maker.key() != escrow_state.maker() {
         return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
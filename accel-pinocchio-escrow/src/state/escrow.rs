@@ -1,7 +1,8 @@
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
+use bytemuck::{Pod, Zeroable};
+use pinocchio_account::PinocchioAccount;
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
 pub struct Escrow {
     maker: [u8; 32],
     mint_a: [u8; 32],
@@ -11,29 +12,13 @@ pub struct Escrow {
     pub bump: u8,
 }
 
-impl Escrow {
-    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 1;
-
-    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
-        let mut data = account_info.try_borrow_mut_data()?;
-        if data.len() != Escrow::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-
-        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
-            return Err(ProgramError::InvalidAccountData);
-        }
-
-        // let mut escrow = Escrow::default();
-
-        // escrow.maker.copy_from_slice(&data[0..32]);
-        // escrow.mint_a.copy_from_slice(&data[32..64]);
-        // escrow.mint_b.copy_from_slice(&data[64..96]);
-        // escrow.amount_to_receive.copy_from_slice(&data[96..104]);
-
-        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
-    }
+impl PinocchioAccount for Escrow {
+    const OWNER: pinocchio::pubkey::Pubkey = crate::ID;
+    // sha256("account:Escrow")[..8]
+    const DISCRIMINATOR: &'static [u8] = &[31, 213, 123, 187, 186, 22, 218, 155];
+}
 
+impl Escrow {
     pub fn maker(&self) -> pinocchio::pubkey::Pubkey {
         pinocchio::pubkey::Pubkey::from(self.maker)
     }
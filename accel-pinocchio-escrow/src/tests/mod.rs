@@ -12,13 +12,17 @@ mod tests {
         CreateAssociatedTokenAccount, CreateMint, MintTo,
     };
 
-    use solana_instruction::{AccountMeta, Instruction};
+    use pinocchio_account::PinocchioAccount;
+    use solana_instruction::{error::InstructionError, AccountMeta, Instruction};
     use solana_keypair::Keypair;
     use solana_message::Message;
     use solana_native_token::LAMPORTS_PER_SOL;
     use solana_pubkey::Pubkey;
     use solana_signer::Signer;
     use solana_transaction::Transaction;
+    use solana_transaction_error::TransactionError;
+
+    use crate::state::Escrow;
 
     const PROGRAM_ID: &str = "4ibrEMW5F6hKnkW4jVedswYv6H6VtwPN6ar6dvXDN1nT";
     const TOKEN_PROGRAM_ID: Pubkey = spl_token::ID;
@@ -395,4 +399,92 @@ mod tests {
         msg!("\n\n Take transaction sucessfull");
         msg!("CUs Consumed: {}", tx.compute_units_consumed);
     }
+
+    #[test]
+    pub fn test_escrow_account_checks() {
+        let (
+            mut svm,
+            payer,
+            mint_a,
+            mint_b,
+            maker_ata_a,
+            escrow,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+
+        MintTo::new(&mut svm, &payer, &mint_a, &maker_ata_a, 1000000000)
+            .send()
+            .unwrap();
+
+        let (escrow_pda, bump) = Pubkey::find_program_address(
+            &[b"escrow".as_ref(), payer.pubkey().as_ref()],
+            &PROGRAM_ID.parse().unwrap(),
+        );
+
+        let transaction = build_make_instruction(
+            &svm,
+            &payer,
+            bump,
+            mint_a,
+            mint_b,
+            escrow_pda,
+            maker_ata_a,
+            vault,
+            system_program,
+            token_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction).unwrap();
+
+        // the escrow is tagged on make
+        let escrow_account = svm.get_account(&escrow).unwrap();
+        assert_eq!(escrow_account.data.len(), Escrow::LEN);
+        assert_eq!(
+            &escrow_account.data[..Escrow::DISCRIMINATOR.len()],
+            Escrow::DISCRIMINATOR
+        );
+
+        let cancel = |svm: &LiteSVM, escrow: Pubkey| {
+            let cancel_ix = Instruction {
+                program_id,
+                accounts: vec![
+                    AccountMeta::new(payer.pubkey(), true),
+                    AccountMeta::new_readonly(mint_a, false),
+                    AccountMeta::new(escrow, false),
+                    AccountMeta::new(maker_ata_a, false),
+                    AccountMeta::new(vault, false),
+                    AccountMeta::new_readonly(system_program, false),
+                    AccountMeta::new_readonly(token_program, false),
+                    AccountMeta::new_readonly(associated_token_program, false),
+                ],
+                data: vec![2u8],
+            };
+            let message = Message::new(&[cancel_ix], Some(&payer.pubkey()));
+            Transaction::new(&[&payer], message, svm.latest_blockhash())
+        };
+
+        // a program account of the right size without the escrow tag
+        let mut forged = escrow_account.clone();
+        forged.data[..Escrow::DISCRIMINATOR.len()].fill(0);
+        svm.set_account(escrow, forged).unwrap();
+        svm.expire_blockhash();
+
+        let failed = svm
+            .send_transaction(cancel(&svm, escrow))
+            .expect_err("cancel with an untagged escrow should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+        );
+
+        svm.set_account(escrow, escrow_account).unwrap();
+        svm.expire_blockhash();
+        svm.send_transaction(cancel(&svm, escrow))
+            .expect("Failed to send cancel txn");
+    }
 }
@@ -0,0 +1,48 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use solana_nostd_sha256::hashv;
+
+use crate::{error::FundraiserError, state::Fundraiser};
+
+/// Leaf the allowlist merkle root commits to for `wallet`.
+pub fn leaf(wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[b"allowlist", wallet.as_ref()])
+}
+
+/// Walks a proof of 32-byte siblings up from `leaf`, hashing each pair in sorted order.
+pub fn verify_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[u8]) -> Result<bool, ProgramError> {
+    if proof.len() % 32 != 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let node = proof.chunks_exact(32).fold(leaf, |node, sibling| {
+        if node.as_slice() <= sibling {
+            hashv(&[&node, sibling])
+        } else {
+            hashv(&[sibling, &node])
+        }
+    });
+    Ok(&node == root)
+}
+
+/// Lets `wallet` through when the allowlist authority signed alongside it,
+/// or when `proof` places it under the allowlist root.
+pub fn check_allowlist(
+    fundraiser: &Fundraiser,
+    wallet: &Pubkey,
+    proof: &[u8],
+    remaining: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let authority = fundraiser.allowlist_authority();
+    let allowed = if authority != [0; 32] {
+        remaining
+            .iter()
+            .any(|account| account.key() == &authority && account.is_signer())
+    } else {
+        verify_proof(&fundraiser.allowlist_root(), leaf(wallet), proof)?
+    };
+
+    if !allowed {
+        return Err(FundraiserError::NotAllowlisted.into());
+    }
+    Ok(())
+}
@@ -1,4 +1,14 @@
 pub const MIN_AMOUNT_TO_RAISE: u64 = 50;
-pub const MAX_CONTRIBUTION_PERCENTAGE: u64 = 100; // for testing purpose
-pub const PERCENTAGE_SCALER: u64 = 100; // for testing purpose
 pub const SECONDS_TO_DAYS: u64 = 86400;
+pub const MAX_DURATION: u64 = 365 * SECONDS_TO_DAYS;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_STRETCH_GOALS: usize = 4;
+pub const NATIVE_DECIMALS: u8 = 9;
+pub const MAX_ACCEPTED_MINTS: usize = 4;
+pub const MAX_PRICE_STALENESS_SLOTS: u64 = 100;
+pub const MIN_PRICE_SAMPLES: u32 = 1;
+pub const MAX_REFERRAL_FEE_BPS: u16 = 2_000;
+pub const SWITCHBOARD_ON_DEMAND_MAINNET: pinocchio::pubkey::Pubkey =
+    pinocchio_pubkey::pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+pub const SWITCHBOARD_ON_DEMAND_DEVNET: pinocchio::pubkey::Pubkey =
+    pinocchio_pubkey::pubkey!("Aio4gaXjXzJNVLtzwtNVmSqGKpANtXhybbkhtAC94ji2");
@@ -0,0 +1,68 @@
+use pinocchio::program_error::ProgramError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FundraiserError {
+    // initialize
+    TargetTooLow = 0,
+    InvalidMinContribution = 1,
+    InvalidMaxContribution = 2,
+    InvalidHardCap = 3,
+    InvalidDuration = 4,
+    InvalidStartTime = 5,
+    InvalidBasisPoints = 6,
+    InvalidStretchGoals = 28,
+    // contribute
+    ContributionTooSmall = 7,
+    ContributionTooLarge = 8,
+    HardCapReached = 9,
+    CampaignNotStarted = 10,
+    CampaignEnded = 11,
+    // collect / refund
+    CampaignNotEnded = 12,
+    TargetNotMet = 13,
+    RefundsLocked = 14,
+    // milestones
+    MilestonesEnabled = 15,
+    MilestonesDisabled = 16,
+    InvalidProposalAmount = 17,
+    InvalidVotingPeriod = 18,
+    ProposalAlreadyExecuted = 19,
+    VotingClosed = 20,
+    VotingOpen = 21,
+    NoVotingWeight = 22,
+    AlreadyVoted = 23,
+    CampaignRefunding = 24,
+    // accounts
+    InvalidPda = 25,
+    TokenAccountMismatch = 26,
+    MathOverflow = 27,
+    RentExemption = 34,
+    // vesting
+    InvalidVesting = 29,
+    VestingEnabled = 30,
+    VestingDisabled = 31,
+    NothingToClaim = 32,
+    AlreadyFlagged = 33,
+    // multi-mint
+    InvalidAcceptedMints = 35,
+    InvalidPriceFeed = 36,
+    StalePrice = 37,
+    // status
+    CampaignCancelled = 38,
+    CampaignNotFailed = 39,
+    InvalidStatus = 40,
+    // allowlist
+    InvalidAllowlist = 41,
+    NotAllowlisted = 42,
+    // referrals
+    InvalidReferralFee = 43,
+    InvalidReferrer = 44,
+    // crank
+    ReceiptsNotDelegated = 45,
+}
+
+impl From<FundraiserError> for ProgramError {
+    fn from(e: FundraiserError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,41 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use pinocchio_account::PinocchioAccount;
+
+use crate::{
+    error::FundraiserError,
+    state::{Fundraiser, FundraiserStatus},
+};
+
+pub fn process_cancel(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [maker, fundraiser, ..] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    };
+
+    if !maker.is_signer() {
+        return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature);
+    }
+
+    let mut fundraiser_state = Fundraiser::load_mut(fundraiser)?;
+    Fundraiser::validate_pda(fundraiser_state.bump(), fundraiser.key(), maker.key())?;
+
+    if fundraiser_state.cancelled() {
+        return Err(FundraiserError::CampaignCancelled.into());
+    }
+
+    // only an ongoing (or not yet started) campaign can be aborted
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time >= fundraiser_state.end_time()? {
+        return Err(FundraiserError::CampaignEnded.into());
+    }
+    fundraiser_state.require_status(current_time, &[FundraiserStatus::Active])?;
+
+    // contributions and collect stop, every contributor can refund in full
+    fundraiser_state.set_status(FundraiserStatus::Cancelled);
+
+    Ok(())
+}
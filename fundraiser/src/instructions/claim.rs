@@ -0,0 +1,81 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use pinocchio_account::PinocchioAccount;
+
+use crate::{
+    error::FundraiserError,
+    instructions::{pay_out, validate_ata},
+    state::{Fundraiser, FundraiserStatus},
+};
+
+pub fn process_claim(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [maker, maker_ata, mint, fundraiser, vault, _token_program @ ..] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    };
+
+    if !maker.is_signer() {
+        return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature);
+    }
+
+    // the fundraiser signs the payout, so work on a copy and write it back after
+    let mut fundraiser_state = *Fundraiser::load(fundraiser)?;
+    Fundraiser::validate_pda(fundraiser_state.bump(), fundraiser.key(), maker.key())?;
+
+    if !fundraiser_state.is_native() {
+        validate_ata(maker_ata, mint, maker)?;
+        validate_ata(vault, mint, fundraiser)?;
+    }
+
+    if !fundraiser_state.vesting_enabled() {
+        return Err(FundraiserError::VestingDisabled.into());
+    }
+    // a flagged maker stops vesting, the rest goes back to contributors
+    if fundraiser_state.cancelled() {
+        return Err(FundraiserError::CampaignCancelled.into());
+    }
+    if fundraiser_state.refunding() {
+        return Err(FundraiserError::CampaignRefunding.into());
+    }
+
+    if fundraiser_state.current_amount() < fundraiser_state.amount_to_raise() {
+        return Err(FundraiserError::TargetNotMet.into());
+    }
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time < fundraiser_state.end_time()? {
+        return Err(FundraiserError::CampaignNotEnded.into());
+    }
+    fundraiser_state.settle(current_time)?;
+    fundraiser_state.require_status(current_time, &[FundraiserStatus::Succeeded])?;
+
+    let amount = fundraiser_state
+        .vested_amount(current_time)?
+        .checked_sub(fundraiser_state.released_amount())
+        .ok_or(FundraiserError::MathOverflow)?;
+    if amount == 0 {
+        return Err(FundraiserError::NothingToClaim.into());
+    }
+
+    pay_out(
+        &fundraiser_state,
+        fundraiser,
+        vault,
+        maker_ata,
+        maker,
+        amount,
+    )?;
+
+    fundraiser_state.set_released_amount(
+        fundraiser_state
+            .released_amount()
+            .checked_add(amount)
+            .ok_or(FundraiserError::MathOverflow)?,
+    );
+    fundraiser_state.mark_collected_if_released();
+    *Fundraiser::load_mut(fundraiser)? = fundraiser_state;
+
+    Ok(())
+}
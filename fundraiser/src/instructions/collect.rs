@@ -1,15 +1,20 @@
 use pinocchio::{
     account_info::AccountInfo,
-    instruction::{Seed, Signer},
     sysvars::{clock::Clock, Sysvar},
     ProgramResult,
 };
-use pinocchio_token::instructions::Transfer;
 
-use crate::{constant::SECONDS_TO_DAYS, instructions::validate_ata, state::Fundraiser};
+use pinocchio_account::PinocchioAccount;
+use pinocchio_token::state::TokenAccount;
+
+use crate::{
+    error::FundraiserError,
+    instructions::{apply_bps, pay_out, validate_ata, validate_token_account},
+    state::{Fundraiser, FundraiserStatus, Referrer},
+};
 
 pub fn process_collect(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [maker, maker_ata, mint, fundraiser, vault, _system_program, _token_program, _associated_token_program @ ..] =
+    let [maker, maker_ata, mint, fundraiser, vault, _system_program, _token_program, _associated_token_program, remaining @ ..] =
         accounts
     else {
         return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
@@ -19,42 +24,142 @@ pub fn process_collect(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
         return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature);
     }
 
-    validate_ata(maker_ata, mint, maker)?;
-    validate_ata(vault, mint, fundraiser)?;
+    if data.is_empty() {
+        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    }
+
+    let fundraiser_state = *Fundraiser::load(fundraiser)?;
+
+    if !fundraiser_state.is_native() {
+        if mint.key() != &fundraiser_state.mint_to_raise() {
+            return Err(FundraiserError::InvalidAcceptedMints.into());
+        }
+        validate_ata(maker_ata, mint, maker)?;
+        validate_ata(vault, mint, fundraiser)?;
+    }
+
+    if fundraiser_state.cancelled() {
+        return Err(FundraiserError::CampaignCancelled.into());
+    }
 
-    let fundraiser_data = fundraiser.try_borrow_data()?;
-    let fundraiser_state = bytemuck::pod_read_unaligned::<Fundraiser>(&fundraiser_data);
+    // milestone campaigns release funds through approved proposals instead
+    if fundraiser_state.milestones_enabled() {
+        return Err(FundraiserError::MilestonesEnabled.into());
+    }
+    // and vesting campaigns through claims
+    if fundraiser_state.vesting_enabled() {
+        return Err(FundraiserError::VestingEnabled.into());
+    }
 
     // check if target amount is met or more
     if fundraiser_state.current_amount() < fundraiser_state.amount_to_raise() {
-        return Err(pinocchio::program_error::ProgramError::InvalidArgument);
+        return Err(FundraiserError::TargetNotMet.into());
     }
     // check if duration passed
     let current_time = Clock::get()?.unix_timestamp as u64;
-    if current_time
-        < fundraiser_state.time_started() + (fundraiser_state.duration() as u64 * SECONDS_TO_DAYS)
-    {
-        return Err(pinocchio::program_error::ProgramError::InvalidArgument);
+    if current_time < fundraiser_state.end_time()? {
+        return Err(FundraiserError::CampaignNotEnded.into());
     }
 
+    fundraiser_state.require_status(current_time, &[FundraiserStatus::Succeeded])?;
+
     let bump = data[0];
     Fundraiser::validate_pda(bump, fundraiser.key(), maker.key())?;
 
-    let bump = [bump.to_le()];
-    let seed = [
-        Seed::from(b"fundraiser"),
-        Seed::from(maker.key()),
-        Seed::from(&bump),
-    ];
-
-    let seeds = Signer::from(&seed);
-    let amount = fundraiser_state.current_amount();
-    Transfer {
-        from: vault,
-        to: maker_ata,
+    // referrers are paid first, one [referrer PDA, referrer token account] pair each;
+    // referral campaigns are single-mint, so the pairs never mix with accepted mints
+    let mut fees = 0u64;
+    if fundraiser_state.referral_fee_bps() != 0 {
+        let referrer_count = fundraiser_state.referrer_count() as usize;
+        if remaining.len() < referrer_count * 2 {
+            return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+        }
+        for pair in remaining.chunks_exact(2).take(referrer_count) {
+            let fee = pay_referrer(&fundraiser_state, fundraiser, vault, &pair[0], &pair[1])?;
+            fees = fees.checked_add(fee).ok_or(FundraiserError::MathOverflow)?;
+        }
+    }
+
+    // current_amount is quoted across mints, so multi-mint campaigns sweep every vault instead
+    let accepted_mints_count = fundraiser_state.accepted_mints_count() as usize;
+    let amount = if fundraiser_state.multi_mint() {
+        TokenAccount::from_account_info(vault)?.amount()
+    } else {
+        fundraiser_state
+            .current_amount()
+            .checked_sub(fees)
+            .ok_or(FundraiserError::MathOverflow)?
+    };
+    pay_out(
+        &fundraiser_state,
+        fundraiser,
+        vault,
+        maker_ata,
+        maker,
         amount,
-        authority: fundraiser,
+    )?;
+
+    // one [vault, maker token account] pair per accepted mint
+    if remaining.len() < accepted_mints_count * 2 {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
     }
-    .invoke_signed(&[seeds])?;
+    for (i, pair) in remaining
+        .chunks_exact(2)
+        .take(accepted_mints_count)
+        .enumerate()
+    {
+        let accepted_mint = fundraiser_state.accepted_mint(i);
+        validate_token_account(&pair[0], &accepted_mint, fundraiser.key())?;
+        validate_token_account(&pair[1], &accepted_mint, maker.key())?;
+
+        let amount = TokenAccount::from_account_info(&pair[0])?.amount();
+        pay_out(
+            &fundraiser_state,
+            fundraiser,
+            &pair[0],
+            &pair[1],
+            maker,
+            amount,
+        )?;
+    }
+
+    Fundraiser::load_mut(fundraiser)?.set_status(FundraiserStatus::Collected);
+
     Ok(())
 }
+
+/// Pays a referrer its fee on the contributions it referred, once.
+fn pay_referrer(
+    fundraiser_state: &Fundraiser,
+    fundraiser: &AccountInfo,
+    vault: &AccountInfo,
+    referrer_pda: &AccountInfo,
+    to: &AccountInfo,
+) -> Result<u64, pinocchio::program_error::ProgramError> {
+    let mut referrer_state = Referrer::load_mut(referrer_pda)?;
+    let referrer = referrer_state.referrer();
+    Referrer::validate_pda(
+        referrer_state.bump(),
+        referrer_pda.key(),
+        fundraiser.key(),
+        &referrer,
+    )?;
+    if referrer_state.paid() {
+        return Err(FundraiserError::InvalidReferrer.into());
+    }
+
+    // native fees go to the referrer wallet itself
+    if fundraiser_state.is_native() {
+        if to.key() != &referrer {
+            return Err(FundraiserError::InvalidReferrer.into());
+        }
+    } else {
+        validate_token_account(to, &fundraiser_state.mint_to_raise(), &referrer)?;
+    }
+
+    let fee = apply_bps(referrer_state.amount(), fundraiser_state.referral_fee_bps())?;
+    pay_out(fundraiser_state, fundraiser, vault, to, to, fee)?;
+    referrer_state.set_paid(true);
+
+    Ok(fee)
+}
@@ -1,20 +1,28 @@
 use pinocchio::{
     account_info::AccountInfo,
     instruction::{Seed, Signer},
-    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    sysvars::{clock::Clock, Sysvar},
     ProgramResult,
 };
-use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::{instructions::Transfer, state::Mint};
+use pinocchio_account::PinocchioAccount;
+use pinocchio_associated_token_account::instructions::CreateIdempotent;
+use pinocchio_log::log;
+use pinocchio_system::instructions::Transfer as SystemTransfer;
+use pinocchio_token::{
+    instructions::{MintTo, Transfer},
+    state::Mint,
+};
 
 use crate::{
-    constant::{MAX_CONTRIBUTION_PERCENTAGE, PERCENTAGE_SCALER, SECONDS_TO_DAYS},
-    instructions::validate_ata,
-    state::{Contributor, Fundraiser},
+    allowlist::check_allowlist,
+    error::FundraiserError,
+    instructions::{apply_bps, validate_ata},
+    oracle::quote_amount,
+    state::{Contributor, Fundraiser, FundraiserStatus, Referrer},
 };
 
 pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [contributor, contributor_ata, contributor_pda, mint, fundraiser, vault, _system_program, _token_program, _associated_token_program @ ..] =
+    let [contributor, contributor_ata, contributor_pda, mint, fundraiser, vault, receipt_mint, contributor_receipt_ata, system_program, token_program, _associated_token_program, remaining @ ..] =
         accounts
     else {
         return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
@@ -25,8 +33,11 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature);
     }
 
-    let mut fundraiser_data = fundraiser.try_borrow_mut_data()?;
-    let fundraiser_state = bytemuck::from_bytes_mut::<Fundraiser>(&mut fundraiser_data);
+    if data.len() < 9 {
+        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    }
+
+    let mut fundraiser_state = Fundraiser::load_mut(fundraiser)?;
     if fundraiser_state.mint_to_raise.is_empty() {
         return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
     }
@@ -36,90 +47,304 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         &fundraiser_state.maker(),
     )?;
 
+    if fundraiser_state.cancelled() {
+        return Err(FundraiserError::CampaignCancelled.into());
+    }
+
+    // allowlisted campaigns take a merkle proof after the referrer bytes, or the authority as a signer
+    if fundraiser_state.allowlisted() {
+        check_allowlist(
+            &fundraiser_state,
+            contributor.key(),
+            data.get(12..).unwrap_or(&[]),
+            remaining,
+        )?;
+    }
+
     let bump = data[0];
 
-    Contributor::validate_pda(bump, &contributor_pda.key(), &contributor.key())?;
+    Contributor::validate_pda(
+        bump,
+        &contributor_pda.key(),
+        &fundraiser.key(),
+        &contributor.key(),
+    )?;
 
-    validate_ata(contributor_ata, mint, contributor)?;
-    validate_ata(vault, mint, fundraiser)?;
+    let native = fundraiser_state.is_native();
 
-    let mint_state = Mint::from_account_info(mint)?;
+    // 0 is mint_to_raise, 1.. the accepted mints
+    let mint_index = data.get(9).copied().unwrap_or(0) as usize;
+    let expected_mint = match mint_index {
+        0 => fundraiser_state.mint_to_raise(),
+        i if i <= fundraiser_state.accepted_mints_count() as usize => {
+            fundraiser_state.accepted_mint(i - 1)
+        }
+        _ => return Err(FundraiserError::InvalidAcceptedMints.into()),
+    };
+    if !native && mint.key() != &expected_mint {
+        return Err(FundraiserError::InvalidAcceptedMints.into());
+    }
+    if !native {
+        validate_ata(contributor_ata, mint, contributor)?;
+        validate_ata(vault, mint, fundraiser)?;
+    }
+    Fundraiser::validate_receipt_mint(
+        fundraiser_state.receipt_bump,
+        receipt_mint.key(),
+        fundraiser.key(),
+    )?;
 
-    let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+    let raw_amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+    let clock = Clock::get()?;
 
-    if amount < 1 * 10u8.pow(mint_state.decimals() as u32) as u64 {
-        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    // every limit below applies to the amount in mint_to_raise units
+    let quoted = if mint_index == 0 {
+        raw_amount
+    } else {
+        let price_feed = remaining
+            .first()
+            .ok_or(pinocchio::program_error::ProgramError::NotEnoughAccountKeys)?;
+        if price_feed.key() != &fundraiser_state.price_feed(mint_index - 1) {
+            return Err(FundraiserError::InvalidPriceFeed.into());
+        }
+        quote_amount(
+            price_feed,
+            clock.slot,
+            raw_amount,
+            Mint::from_account_info(mint)?.decimals(),
+            fundraiser_state.decimals(),
+        )?
+    };
+    let mut amount = quoted;
+
+    // [referrer, referrer PDA] follow the price feed when the contribution was referred
+    let referrer_accounts = if data.get(10).copied().unwrap_or(0) != 0 {
+        let offset = (mint_index != 0) as usize;
+        let accounts = remaining
+            .get(offset..offset + 2)
+            .ok_or(pinocchio::program_error::ProgramError::NotEnoughAccountKeys)?;
+        if data.len() < 12 {
+            return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+        }
+        if fundraiser_state.referral_fee_bps() == 0 || accounts[0].key() == contributor.key() {
+            return Err(FundraiserError::InvalidReferrer.into());
+        }
+        Some([&accounts[0], &accounts[1]])
+    } else {
+        None
+    };
+
+    if amount < fundraiser_state.min_contribution() {
+        return Err(FundraiserError::ContributionTooSmall.into());
     }
 
-    if amount
-        >= (fundraiser_state.amount_to_raise() * MAX_CONTRIBUTION_PERCENTAGE) / PERCENTAGE_SCALER
-    {
-        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    let current_time = clock.unix_timestamp as u64;
+
+    if current_time < fundraiser_state.time_started() {
+        return Err(FundraiserError::CampaignNotStarted.into());
+    }
+    if current_time >= fundraiser_state.end_time()? {
+        return Err(FundraiserError::CampaignEnded.into());
     }
+    fundraiser_state.require_status(current_time, &[FundraiserStatus::Active])?;
 
-    let current_time = Clock::get()?.unix_timestamp as u64;
+    // the contribution that hits the hard cap is clipped to the room left,
+    // the excess simply never leaves the contributor's token account
+    if fundraiser_state.hard_cap() != 0 {
+        let room = fundraiser_state
+            .hard_cap()
+            .checked_sub(fundraiser_state.current_amount())
+            .ok_or(FundraiserError::MathOverflow)?;
+        if room == 0 {
+            return Err(FundraiserError::HardCapReached.into());
+        }
+        if amount > room {
+            log!("Contribution clipped to {} by the hard cap", room);
+            amount = room;
+        }
+    }
 
-    if current_time
-        >= fundraiser_state.time_started() + (fundraiser_state.duration() as u64 * SECONDS_TO_DAYS)
-    {
-        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    // a clipped contribution only moves its share of the raw amount
+    let transfer_amount = if amount == quoted {
+        raw_amount
+    } else {
+        let share = (raw_amount as u128)
+            .checked_mul(amount as u128)
+            .and_then(|v| v.checked_div(quoted as u128))
+            .ok_or(FundraiserError::MathOverflow)?;
+        u64::try_from(share).map_err(|_| FundraiserError::MathOverflow)?
     };
 
+    let new_current_amount = fundraiser_state
+        .current_amount()
+        .checked_add(amount)
+        .ok_or(FundraiserError::MathOverflow)?;
+
     let bump = [bump.to_le()];
     let seed = [
         Seed::from(b"contributor"),
+        Seed::from(fundraiser.key()),
         Seed::from(contributor.key()),
         Seed::from(&bump),
     ];
 
     let seeds = Signer::from(&seed);
 
-    if contributor.owner() != &crate::ID {
-        CreateAccount {
-            from: contributor,
-            to: contributor_pda,
-            owner: &crate::ID,
-            space: Contributor::LEN as u64,
-            lamports: Rent::get()?.minimum_balance(Contributor::LEN),
-        }
-        .invoke_signed(&[seeds])?;
+    // first contribution creates the PDA, later ones top it up
+    let mut contributor_state = if contributor_pda.owner() != &crate::ID {
+        Contributor::init(contributor_pda, contributor, &[seeds])?
     } else {
-        return Err(pinocchio::program_error::ProgramError::IllegalOwner);
+        Contributor::load_mut(contributor_pda)?
+    };
+
+    let max_contribution = apply_bps(
+        fundraiser_state.amount_to_raise(),
+        fundraiser_state.max_contribution_bps(),
+    )?;
+    let new_contributor_amount = contributor_state
+        .amount()
+        .checked_add(amount)
+        .ok_or(FundraiserError::MathOverflow)?;
+    if new_contributor_amount > max_contribution {
+        return Err(FundraiserError::ContributionTooLarge.into());
+    }
+
+    // a referred contribution counts toward its referrer's total, in the quote unit
+    if let Some([referrer, referrer_pda]) = referrer_accounts {
+        add_referral(
+            &mut fundraiser_state,
+            fundraiser,
+            contributor,
+            referrer,
+            referrer_pda,
+            data[11],
+            amount,
+        )?;
     }
 
-    let mut contributor_data = contributor_pda.try_borrow_mut_data()?;
-    let contributor_state = bytemuck::from_bytes_mut::<Contributor>(&mut contributor_data);
+    fundraiser_state.set_current_amount(new_current_amount);
+    contributor_state.set_amount(new_contributor_amount);
+    contributor_state.bump = data[0];
+    contributor_state.set_deposit(
+        mint_index,
+        contributor_state
+            .deposit(mint_index)
+            .checked_add(transfer_amount)
+            .ok_or(FundraiserError::MathOverflow)?,
+    );
 
-    if (contributor_state.amount()
-        >= (fundraiser_state.amount_to_raise() * MAX_CONTRIBUTION_PERCENTAGE) / PERCENTAGE_SCALER)
-        && (contributor_state.amount() + amount
-            >= (fundraiser_state.amount_to_raise() * MAX_CONTRIBUTION_PERCENTAGE)
-                / PERCENTAGE_SCALER)
+    let mut reached = fundraiser_state.stretch_goals_reached();
+    while reached < fundraiser_state.stretch_goals_count()
+        && new_current_amount >= fundraiser_state.stretch_goal(reached as usize)
     {
-        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+        log!(
+            "Stretch goal {} reached: {}",
+            reached,
+            fundraiser_state.stretch_goal(reached as usize)
+        );
+        reached += 1;
     }
+    fundraiser_state.set_stretch_goals_reached(reached);
 
-    // transfer from contributor_ata to vault
-    Transfer {
-        from: contributor_ata,
-        to: vault,
-        amount: amount,
-        authority: contributor,
+    let maker = fundraiser_state.maker();
+    let bump = [fundraiser_state.bump.to_le()];
+    drop(contributor_state);
+    drop(fundraiser_state);
+
+    if native {
+        // lamports go straight into the fundraiser PDA
+        SystemTransfer {
+            from: contributor,
+            to: fundraiser,
+            lamports: transfer_amount,
+        }
+        .invoke()?;
+    } else {
+        // transfer from contributor_ata to vault
+        Transfer {
+            from: contributor_ata,
+            to: vault,
+            amount: transfer_amount,
+            authority: contributor,
+        }
+        .invoke()?;
+    }
+
+    // mint receipts 1:1 for what actually reached the vault
+
+    CreateIdempotent {
+        funding_account: contributor,
+        account: contributor_receipt_ata,
+        wallet: contributor,
+        mint: receipt_mint,
+        system_program,
+        token_program,
     }
     .invoke()?;
 
-    fundraiser_state.set_current_amount(
-        fundraiser_state
-            .current_amount()
-            .checked_add(amount)
-            .expect("Failed to add amount to current amount!"),
-    );
+    let seed = [
+        Seed::from(b"fundraiser"),
+        Seed::from(&maker),
+        Seed::from(&bump),
+    ];
 
-    contributor_state.set_amount(
-        contributor_state
+    let seeds = Signer::from(&seed);
+    MintTo {
+        mint: receipt_mint,
+        account: contributor_receipt_ata,
+        mint_authority: fundraiser,
+        amount,
+    }
+    .invoke_signed(&[seeds])?;
+
+    Ok(())
+}
+
+/// Adds `amount` to the referrer's total, creating its PDA on the first referral.
+fn add_referral(
+    fundraiser_state: &mut Fundraiser,
+    fundraiser: &AccountInfo,
+    payer: &AccountInfo,
+    referrer: &AccountInfo,
+    referrer_pda: &AccountInfo,
+    bump: u8,
+    amount: u64,
+) -> ProgramResult {
+    Referrer::validate_pda(bump, referrer_pda.key(), fundraiser.key(), referrer.key())?;
+
+    if referrer_pda.owner() != &crate::ID {
+        let bump_seed = [bump.to_le()];
+        let seed = [
+            Seed::from(b"referrer"),
+            Seed::from(fundraiser.key()),
+            Seed::from(referrer.key()),
+            Seed::from(&bump_seed),
+        ];
+
+        let mut referrer_state = Referrer::init(referrer_pda, payer, &[Signer::from(&seed)])?;
+        referrer_state.set_referrer(referrer.key());
+        referrer_state.bump = bump;
+        fundraiser_state.set_referrer_count(
+            fundraiser_state
+                .referrer_count()
+                .checked_add(1)
+                .ok_or(FundraiserError::MathOverflow)?,
+        );
+    }
+
+    let mut referrer_state = Referrer::load_mut(referrer_pda)?;
+    referrer_state.set_amount(
+        referrer_state
             .amount()
             .checked_add(amount)
-            .expect("Failed to update amount in contributor PDA!"),
+            .ok_or(FundraiserError::MathOverflow)?,
     );
+    fundraiser_state.set_referred_amount(
+        fundraiser_state
+            .referred_amount()
+            .checked_add(amount)
+            .ok_or(FundraiserError::MathOverflow)?,
+    );
+
     Ok(())
 }
@@ -0,0 +1,125 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use pinocchio_account::PinocchioAccount;
+use pinocchio_token::{instructions::Burn, state::TokenAccount};
+
+use crate::{
+    error::FundraiserError,
+    instructions::{close_account, pay_out, validate_token_account},
+    state::{Contributor, Fundraiser, FundraiserStatus},
+};
+
+pub fn process_crank_refunds(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [fundraiser, vault, receipt_mint, _token_program, contributors @ ..] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    };
+
+    let fundraiser_state = *Fundraiser::load(fundraiser)?;
+
+    Fundraiser::validate_pda(
+        fundraiser_state.bump(),
+        fundraiser.key(),
+        &fundraiser_state.maker(),
+    )?;
+    Fundraiser::validate_receipt_mint(
+        fundraiser_state.receipt_bump,
+        receipt_mint.key(),
+        fundraiser.key(),
+    )?;
+
+    // anyone can push the refunds out, but only once the campaign can't succeed anymore
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if !matches!(
+        fundraiser_state.status_at(current_time)?,
+        FundraiserStatus::Failed | FundraiserStatus::Cancelled
+    ) {
+        return Err(FundraiserError::CampaignNotFailed.into());
+    }
+
+    // accepted mints need the contributor's token accounts for every mint, those refund themselves
+    if fundraiser_state.multi_mint() {
+        return Err(FundraiserError::InvalidAcceptedMints.into());
+    }
+
+    let mint_to_raise = fundraiser_state.mint_to_raise();
+    if !fundraiser_state.is_native() {
+        validate_token_account(vault, &mint_to_raise, fundraiser.key())?;
+    }
+
+    // one [contributor pda, contributor ata, contributor wallet, receipt ata] group per
+    // refund, the wallet gets the pda rent back (and the lamports of a native campaign)
+    let groups = contributors.chunks_exact(4);
+    if !groups.remainder().is_empty() {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    }
+
+    let maker = fundraiser_state.maker();
+    let bump = [fundraiser_state.bump.to_le()];
+    let seed = [
+        Seed::from(b"fundraiser"),
+        Seed::from(&maker),
+        Seed::from(&bump),
+    ];
+
+    for group in groups {
+        let [contributor_pda, contributor_ata, contributor, receipt_ata] = group else {
+            unreachable!()
+        };
+
+        // already refunded, or closed by an earlier crank in the same batch
+        if contributor_pda.owner() != &crate::ID {
+            continue;
+        }
+
+        let contributor_state = *Contributor::load(contributor_pda)?;
+
+        Contributor::validate_pda(
+            contributor_state.bump(),
+            contributor_pda.key(),
+            fundraiser.key(),
+            contributor.key(),
+        )?;
+
+        let amount = fundraiser_state.refund_amount(&contributor_state)?;
+
+        if !fundraiser_state.is_native() {
+            validate_token_account(contributor_ata, &mint_to_raise, contributor.key())?;
+        }
+
+        // the contributor approves the fundraiser to burn their receipts, as Refund would
+        validate_token_account(receipt_ata, receipt_mint.key(), contributor.key())?;
+        {
+            let receipts = TokenAccount::from_account_info(receipt_ata)?;
+            if receipts.delegate() != Some(fundraiser.key())
+                || receipts.delegated_amount() < contributor_state.amount()
+            {
+                return Err(FundraiserError::ReceiptsNotDelegated.into());
+            }
+        }
+        Burn {
+            account: receipt_ata,
+            mint: receipt_mint,
+            authority: fundraiser,
+            amount: contributor_state.amount(),
+        }
+        .invoke_signed(&[Signer::from(&seed)])?;
+
+        pay_out(
+            &fundraiser_state,
+            fundraiser,
+            vault,
+            contributor_ata,
+            contributor,
+            amount,
+        )?;
+
+        close_account(contributor_pda, contributor)?;
+    }
+
+    Ok(())
+}
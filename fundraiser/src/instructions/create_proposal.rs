@@ -0,0 +1,100 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_account::PinocchioAccount;
+
+use crate::{
+    error::FundraiserError,
+    state::{Fundraiser, FundraiserStatus, Proposal},
+};
+
+pub fn process_create_proposal(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [maker, fundraiser, proposal, _system_program @ ..] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    };
+
+    // checks:
+    if !maker.is_signer() {
+        return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature);
+    }
+
+    let mut fundraiser_state = Fundraiser::load_mut(fundraiser)?;
+    Fundraiser::validate_pda(fundraiser_state.bump(), fundraiser.key(), maker.key())?;
+
+    if !fundraiser_state.milestones_enabled() {
+        return Err(FundraiserError::MilestonesDisabled.into());
+    }
+    if fundraiser_state.cancelled() {
+        return Err(FundraiserError::CampaignCancelled.into());
+    }
+    if fundraiser_state.refunding() {
+        return Err(FundraiserError::CampaignRefunding.into());
+    }
+
+    // milestones can only be proposed once the campaign has succeeded
+    if fundraiser_state.current_amount() < fundraiser_state.amount_to_raise() {
+        return Err(FundraiserError::TargetNotMet.into());
+    }
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time < fundraiser_state.end_time()? {
+        return Err(FundraiserError::CampaignNotEnded.into());
+    }
+    fundraiser_state.settle(current_time)?;
+    fundraiser_state.require_status(current_time, &[FundraiserStatus::Succeeded])?;
+
+    if data.len() < 17 {
+        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    }
+
+    let bump = data[0];
+    let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+    let voting_period = u64::from_le_bytes(data[9..17].try_into().unwrap());
+
+    let remaining = fundraiser_state
+        .current_amount()
+        .checked_sub(fundraiser_state.released_amount())
+        .ok_or(FundraiserError::MathOverflow)?;
+    if amount == 0 || amount > remaining {
+        return Err(FundraiserError::InvalidProposalAmount.into());
+    }
+    let voting_ends_at = current_time
+        .checked_add(voting_period)
+        .ok_or(FundraiserError::InvalidVotingPeriod)?;
+    if voting_period == 0 {
+        return Err(FundraiserError::InvalidVotingPeriod.into());
+    }
+
+    let id = fundraiser_state.proposal_count();
+    Proposal::validate_pda(bump, proposal.key(), fundraiser.key(), id)?;
+
+    if proposal.owner() == &crate::ID {
+        return Err(pinocchio::program_error::ProgramError::IllegalOwner);
+    }
+
+    let id_bytes = id.to_le_bytes();
+    let bump_seed = [bump.to_le()];
+    let seed = [
+        Seed::from(b"proposal"),
+        Seed::from(fundraiser.key()),
+        Seed::from(&id_bytes),
+        Seed::from(&bump_seed),
+    ];
+
+    let seeds = Signer::from(&seed);
+    let mut proposal_state = Proposal::init(proposal, maker, &[seeds])?;
+    proposal_state.set_fundraiser(fundraiser.key());
+    proposal_state.set_id(id);
+    proposal_state.set_amount(amount);
+    proposal_state.set_votes_for(0);
+    proposal_state.set_votes_against(0);
+    proposal_state.set_voting_ends_at(voting_ends_at);
+    proposal_state.set_executed(false);
+    proposal_state.bump = bump;
+
+    fundraiser_state.set_proposal_count(id.checked_add(1).ok_or(FundraiserError::MathOverflow)?);
+
+    Ok(())
+}
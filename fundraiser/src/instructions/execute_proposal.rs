@@ -0,0 +1,112 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_account::PinocchioAccount;
+use pinocchio_log::log;
+
+use crate::{
+    error::FundraiserError,
+    instructions::{apply_bps, pay_out, validate_ata},
+    state::{Fundraiser, FundraiserStatus, Proposal},
+};
+
+pub fn process_execute_proposal(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [maker, maker_ata, mint, fundraiser, vault, proposal, _token_program @ ..] = accounts
+    else {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    };
+
+    // permissionless: anyone can settle a proposal once its voting window has closed
+    if fundraiser.owner() != &crate::ID || proposal.owner() != &crate::ID {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountOwner);
+    }
+
+    // the fundraiser signs the release, so work on a copy and write it back after
+    let mut fundraiser_state = *Fundraiser::load(fundraiser)?;
+    Fundraiser::validate_pda(fundraiser_state.bump(), fundraiser.key(), maker.key())?;
+
+    if !fundraiser_state.is_native() {
+        validate_ata(maker_ata, mint, maker)?;
+        validate_ata(vault, mint, fundraiser)?;
+    }
+
+    let mut proposal_state = Proposal::load_mut(proposal)?;
+    if proposal_state.fundraiser() != *fundraiser.key() {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    }
+    if proposal_state.executed() {
+        return Err(FundraiserError::ProposalAlreadyExecuted.into());
+    }
+    if fundraiser_state.cancelled() {
+        return Err(FundraiserError::CampaignCancelled.into());
+    }
+    if fundraiser_state.refunding() {
+        return Err(FundraiserError::CampaignRefunding.into());
+    }
+
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time < proposal_state.voting_ends_at() {
+        return Err(FundraiserError::VotingOpen.into());
+    }
+    fundraiser_state.require_status(current_time, &[FundraiserStatus::Succeeded])?;
+
+    proposal_state.set_executed(true);
+
+    let votes_for = proposal_state.votes_for();
+    let total_votes = votes_for
+        .checked_add(proposal_state.votes_against())
+        .ok_or(FundraiserError::MathOverflow)?;
+
+    // quorum is measured against the whole raise, threshold against the votes cast
+    let quorum_met = total_votes
+        >= apply_bps(
+            fundraiser_state.current_amount(),
+            fundraiser_state.quorum_bps(),
+        )?;
+    let approved =
+        total_votes > 0 && votes_for >= apply_bps(total_votes, fundraiser_state.threshold_bps())?;
+
+    if !(quorum_met && approved) {
+        // a rejected milestone opens refunds of whatever is left in the vault
+        fundraiser_state.set_status(FundraiserStatus::Failed);
+        *Fundraiser::load_mut(fundraiser)? = fundraiser_state;
+        return Ok(());
+    }
+
+    // proposals can overlap, so one approved after an earlier release may no longer
+    // fit: it is settled without paying out, and the campaign carries on
+    let remaining = fundraiser_state
+        .current_amount()
+        .checked_sub(fundraiser_state.released_amount())
+        .ok_or(FundraiserError::MathOverflow)?;
+    if proposal_state.amount() > remaining {
+        log!(
+            "Proposal {} exceeds the remaining {}",
+            proposal_state.id(),
+            remaining
+        );
+        return Ok(());
+    }
+
+    pay_out(
+        &fundraiser_state,
+        fundraiser,
+        vault,
+        maker_ata,
+        maker,
+        proposal_state.amount(),
+    )?;
+
+    fundraiser_state.set_released_amount(
+        fundraiser_state
+            .released_amount()
+            .checked_add(proposal_state.amount())
+            .ok_or(FundraiserError::MathOverflow)?,
+    );
+    fundraiser_state.mark_collected_if_released();
+    *Fundraiser::load_mut(fundraiser)? = fundraiser_state;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_account::PinocchioAccount;
+use pinocchio_log::log;
+
+use crate::{
+    error::FundraiserError,
+    state::{Fundraiser, FundraiserStatus},
+};
+
+pub fn process_finalize(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [fundraiser, ..] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    };
+
+    let mut fundraiser_state = Fundraiser::load_mut(fundraiser)?;
+    Fundraiser::validate_pda(
+        fundraiser_state.bump(),
+        fundraiser.key(),
+        &fundraiser_state.maker(),
+    )?;
+
+    // anyone can settle an ended campaign, but only once
+    match fundraiser_state.status()? {
+        FundraiserStatus::Active => {}
+        FundraiserStatus::Cancelled => return Err(FundraiserError::CampaignCancelled.into()),
+        _ => return Err(FundraiserError::InvalidStatus.into()),
+    }
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time < fundraiser_state.end_time()? {
+        return Err(FundraiserError::CampaignNotEnded.into());
+    }
+
+    let status = fundraiser_state.settle(current_time)?;
+    log!("Fundraiser finalized as {}", status as u8);
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_account::PinocchioAccount;
+use pinocchio_log::log;
+
+use crate::{
+    error::FundraiserError,
+    instructions::apply_bps,
+    state::{Contributor, Fundraiser, FundraiserStatus},
+};
+
+pub fn process_flag(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [contributor, contributor_pda, fundraiser, ..] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    };
+
+    if !contributor.is_signer() {
+        return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature);
+    }
+
+    if contributor_pda.owner() != &crate::ID || fundraiser.owner() != &crate::ID {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountOwner);
+    }
+
+    if data.is_empty() {
+        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    }
+
+    Contributor::validate_pda(
+        data[0],
+        contributor_pda.key(),
+        fundraiser.key(),
+        contributor.key(),
+    )?;
+
+    let mut fundraiser_state = Fundraiser::load_mut(fundraiser)?;
+    if !fundraiser_state.vesting_enabled() {
+        return Err(FundraiserError::VestingDisabled.into());
+    }
+    if fundraiser_state.cancelled() {
+        return Err(FundraiserError::CampaignCancelled.into());
+    }
+    if fundraiser_state.refunding() {
+        return Err(FundraiserError::CampaignRefunding.into());
+    }
+
+    // only a succeeded campaign vests, before that contributors can simply refund
+    if fundraiser_state.current_amount() < fundraiser_state.amount_to_raise() {
+        return Err(FundraiserError::TargetNotMet.into());
+    }
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time < fundraiser_state.end_time()? {
+        return Err(FundraiserError::CampaignNotEnded.into());
+    }
+    fundraiser_state.settle(current_time)?;
+    fundraiser_state.require_status(current_time, &[FundraiserStatus::Succeeded])?;
+
+    let mut contributor_state = Contributor::load_mut(contributor_pda)?;
+    if contributor_state.flagged() {
+        return Err(FundraiserError::AlreadyFlagged.into());
+    }
+    if contributor_state.amount() == 0 {
+        return Err(FundraiserError::NoVotingWeight.into());
+    }
+    contributor_state.set_flagged(true);
+
+    let flagged_amount = fundraiser_state
+        .flagged_amount()
+        .checked_add(contributor_state.amount())
+        .ok_or(FundraiserError::MathOverflow)?;
+    fundraiser_state.set_flagged_amount(flagged_amount);
+
+    // a quorum of flags stops vesting and opens refunds of the unvested remainder
+    let quorum = apply_bps(
+        fundraiser_state.current_amount(),
+        fundraiser_state.quorum_bps(),
+    )?;
+    if flagged_amount >= quorum {
+        log!("Vesting flagged with {} of {}", flagged_amount, quorum);
+        fundraiser_state.set_status(FundraiserStatus::Failed);
+    }
+
+    Ok(())
+}
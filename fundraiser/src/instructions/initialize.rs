@@ -4,13 +4,23 @@ use pinocchio::{
     sysvars::{clock::Clock, rent::Rent, Sysvar},
     ProgramResult,
 };
+use pinocchio_account::PinocchioAccount;
 use pinocchio_associated_token_account::instructions::CreateIdempotent;
 use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::{instructions::InitializeMint2, state::Mint};
 
-use crate::{constant::MIN_AMOUNT_TO_RAISE, state::Fundraiser};
+use crate::{
+    constant::{
+        BPS_DENOMINATOR, MAX_ACCEPTED_MINTS, MAX_DURATION, MAX_REFERRAL_FEE_BPS, MAX_STRETCH_GOALS,
+        MIN_AMOUNT_TO_RAISE, NATIVE_DECIMALS,
+    },
+    error::FundraiserError,
+    oracle::validate_price_feed,
+    state::{Fundraiser, FundraiserStatus},
+};
 
 pub fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [maker, mint, fundraiser, vault, system_program, token_program, _associated_token_program, _rent_sysvar @ ..] =
+    let [maker, mint, fundraiser, vault, receipt_mint, system_program, token_program, _associated_token_program, _rent_sysvar, accepted @ ..] =
         accounts
     else {
         return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
@@ -21,17 +31,149 @@ pub fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature);
     }
 
+    if data.len() < 48 {
+        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    }
+
     let bump = data[0];
 
     Fundraiser::validate_pda(bump, fundraiser.key(), maker.key())?;
 
     let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
-    let duration = data[9];
+    let min_contribution = u64::from_le_bytes(data[9..17].try_into().unwrap());
+    let max_contribution_bps = u16::from_le_bytes(data[17..19].try_into().unwrap());
+    let hard_cap = u64::from_le_bytes(data[19..27].try_into().unwrap());
+    let start_time = u64::from_le_bytes(data[27..35].try_into().unwrap());
+    let duration = u64::from_le_bytes(data[35..43].try_into().unwrap());
+    let quorum_bps = u16::from_le_bytes(data[43..45].try_into().unwrap());
+    let threshold_bps = u16::from_le_bytes(data[45..47].try_into().unwrap());
+    let stretch_goals_count = data[47] as usize;
+    if stretch_goals_count > MAX_STRETCH_GOALS {
+        return Err(FundraiserError::InvalidStretchGoals.into());
+    }
+    let tail = 48 + stretch_goals_count * 8;
+    if data.len() < tail + 84 {
+        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    }
+    let stretch_goals_data = &data[48..tail];
+    let receipt_bump = data[tail];
+    let vesting_duration = u64::from_le_bytes(data[tail + 1..tail + 9].try_into().unwrap());
+    let vesting_cliff = u64::from_le_bytes(data[tail + 9..tail + 17].try_into().unwrap());
+    let accepted_mints_count = data[tail + 17] as usize;
+    let allowlist_root: [u8; 32] = data[tail + 18..tail + 50].try_into().unwrap();
+    let allowlist_authority: [u8; 32] = data[tail + 50..tail + 82].try_into().unwrap();
+    let referral_fee_bps = u16::from_le_bytes(data[tail + 82..tail + 84].try_into().unwrap());
 
-    let mint_state = pinocchio_token::state::Mint::from_account_info(mint)?;
+    Fundraiser::validate_receipt_mint(receipt_bump, receipt_mint.key(), fundraiser.key())?;
 
-    if amount < MIN_AMOUNT_TO_RAISE * 10u64.pow(mint_state.decimals() as u32) {
-        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    if quorum_bps as u64 > BPS_DENOMINATOR || threshold_bps as u64 > BPS_DENOMINATOR {
+        return Err(FundraiserError::InvalidBasisPoints.into());
+    }
+
+    // passing the system program as the mint raises native SOL into the fundraiser PDA
+    let native = mint.key() == &pinocchio_system::ID;
+    let decimals = if native {
+        NATIVE_DECIMALS
+    } else {
+        Mint::from_account_info(mint)?.decimals()
+    };
+
+    // mints with many decimals can push the minimum target past u64
+    let min_amount_to_raise = 10u64
+        .checked_pow(decimals as u32)
+        .and_then(|unit| unit.checked_mul(MIN_AMOUNT_TO_RAISE))
+        .ok_or(FundraiserError::MathOverflow)?;
+    if amount < min_amount_to_raise {
+        return Err(FundraiserError::TargetTooLow.into());
+    }
+
+    // campaign parameters
+    if min_contribution == 0 || min_contribution > amount {
+        return Err(FundraiserError::InvalidMinContribution.into());
+    }
+    if max_contribution_bps == 0 || max_contribution_bps as u64 > BPS_DENOMINATOR {
+        return Err(FundraiserError::InvalidMaxContribution.into());
+    }
+    if hard_cap != 0 && hard_cap < amount {
+        return Err(FundraiserError::InvalidHardCap.into());
+    }
+    if duration == 0 || duration > MAX_DURATION {
+        return Err(FundraiserError::InvalidDuration.into());
+    }
+
+    // a start time of 0 opens the campaign right away, otherwise it can't be in the past
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    let start_time = if start_time == 0 {
+        current_time
+    } else if start_time < current_time {
+        return Err(FundraiserError::InvalidStartTime.into());
+    } else {
+        start_time
+    };
+    start_time
+        .checked_add(duration)
+        .ok_or(FundraiserError::InvalidStartTime)?;
+
+    // vesting streams the raise to the maker, contributors flag it through the quorum instead of milestones
+    if vesting_duration > MAX_DURATION
+        || vesting_cliff > vesting_duration
+        || (vesting_duration != 0 && (threshold_bps != 0 || quorum_bps == 0))
+    {
+        return Err(FundraiserError::InvalidVesting.into());
+    }
+
+    // extra mints are priced into mint_to_raise; they don't mix with native SOL, nor with
+    // milestones and vesting, whose releases only come out of the main vault
+    if accepted_mints_count > MAX_ACCEPTED_MINTS
+        || (accepted_mints_count != 0 && (native || threshold_bps != 0 || vesting_duration != 0))
+    {
+        return Err(FundraiserError::InvalidAcceptedMints.into());
+    }
+    // one [mint, price feed, vault] triple per accepted mint
+    if accepted.len() < accepted_mints_count * 3 {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    }
+    let accepted = &accepted[..accepted_mints_count * 3];
+    for (i, triple) in accepted.chunks_exact(3).enumerate() {
+        let accepted_mint = &triple[0];
+        Mint::from_account_info(accepted_mint)?;
+        validate_price_feed(&triple[1])?;
+
+        let duplicate = accepted
+            .chunks_exact(3)
+            .take(i)
+            .any(|other| other[0].key() == accepted_mint.key());
+        if accepted_mint.key() == mint.key() || duplicate {
+            return Err(FundraiserError::InvalidAcceptedMints.into());
+        }
+    }
+
+    // a campaign is gated either by a merkle root of wallets or by an authority co-signing
+    if allowlist_root != [0; 32] && allowlist_authority != [0; 32] {
+        return Err(FundraiserError::InvalidAllowlist.into());
+    }
+
+    // referral fees come out of Collect, so only campaigns collecting a single vault pay them
+    if referral_fee_bps > MAX_REFERRAL_FEE_BPS
+        || (referral_fee_bps != 0
+            && (threshold_bps != 0 || vesting_duration != 0 || accepted_mints_count != 0))
+    {
+        return Err(FundraiserError::InvalidReferralFee.into());
+    }
+
+    // stretch goals sit strictly above the target, ascending, and within the hard cap
+    let mut stretch_goals = [0u64; MAX_STRETCH_GOALS];
+    let mut previous = amount;
+    for (i, goal) in stretch_goals
+        .iter_mut()
+        .take(stretch_goals_count)
+        .enumerate()
+    {
+        *goal = u64::from_le_bytes(stretch_goals_data[i * 8..i * 8 + 8].try_into().unwrap());
+        if *goal <= previous || (hard_cap != 0 && *goal > hard_cap) {
+            return Err(FundraiserError::InvalidStretchGoals.into());
+        }
+        previous = *goal;
     }
 
     if fundraiser.owner() != &crate::ID {
@@ -43,39 +185,97 @@ pub fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         ];
 
         let seeds = Signer::from(&seed);
-        CreateAccount {
-            from: maker,
-            to: fundraiser,
-            owner: &crate::ID,
-            space: Fundraiser::LEN as u64,
-            lamports: Rent::get()?.minimum_balance(Fundraiser::LEN),
-        }
-        .invoke_signed(&[seeds])?;
-
         {
-            let mut account_data = fundraiser.try_borrow_mut_data()?;
-            let fundraiser_state = bytemuck::try_from_bytes_mut::<Fundraiser>(&mut account_data)
-                .map_err(|_| pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+            let mut fundraiser_state = Fundraiser::init(fundraiser, maker, &[seeds])?;
 
             fundraiser_state.set_maker(maker.key());
             fundraiser_state.set_mint_to_raise(mint.key());
             fundraiser_state.set_amount_to_raise(amount);
             fundraiser_state.set_current_amount(0);
-            fundraiser_state.set_time_started(Clock::get()?.unix_timestamp as u64);
+            fundraiser_state.set_time_started(start_time);
             fundraiser_state.set_duration(duration);
+            fundraiser_state.set_min_contribution(min_contribution);
+            fundraiser_state.set_max_contribution_bps(max_contribution_bps);
+            fundraiser_state.set_hard_cap(hard_cap);
             fundraiser_state.bump = data[0];
+            fundraiser_state.set_quorum_bps(quorum_bps);
+            fundraiser_state.set_threshold_bps(threshold_bps);
+            fundraiser_state.set_released_amount(0);
+            fundraiser_state.set_proposal_count(0);
+            for (i, goal) in stretch_goals.iter().enumerate() {
+                fundraiser_state.set_stretch_goal(i, *goal);
+            }
+            fundraiser_state.set_stretch_goals_count(stretch_goals_count as u8);
+            fundraiser_state.set_stretch_goals_reached(0);
+            fundraiser_state.receipt_bump = receipt_bump;
+            fundraiser_state.set_vesting_duration(vesting_duration);
+            fundraiser_state.set_vesting_cliff(vesting_cliff);
+            fundraiser_state.set_flagged_amount(0);
+            fundraiser_state.set_native(native);
+            fundraiser_state.set_decimals(decimals);
+            fundraiser_state.set_status(FundraiserStatus::Active);
+            fundraiser_state.set_allowlist_root(&allowlist_root);
+            fundraiser_state.set_allowlist_authority(&allowlist_authority);
+            fundraiser_state.set_referral_fee_bps(referral_fee_bps);
+            fundraiser_state.set_referred_amount(0);
+            fundraiser_state.set_referrer_count(0);
+            fundraiser_state.set_accepted_mints_count(accepted_mints_count as u8);
+            for (i, triple) in accepted.chunks_exact(3).enumerate() {
+                fundraiser_state.set_accepted_mint(i, triple[0].key());
+                fundraiser_state.set_price_feed(i, triple[1].key());
+            }
         }
     } else {
         return Err(pinocchio::program_error::ProgramError::IllegalOwner);
     }
 
-    CreateIdempotent {
-        funding_account: maker,
-        account: vault,
-        wallet: fundraiser,
-        mint,
-        system_program,
-        token_program,
+    if !native {
+        CreateIdempotent {
+            funding_account: maker,
+            account: vault,
+            wallet: fundraiser,
+            mint,
+            system_program,
+            token_program,
+        }
+        .invoke()?;
+    }
+
+    for triple in accepted.chunks_exact(3) {
+        CreateIdempotent {
+            funding_account: maker,
+            account: &triple[2],
+            wallet: fundraiser,
+            mint: &triple[0],
+            system_program,
+            token_program,
+        }
+        .invoke()?;
+    }
+
+    // receipt token for backers, same decimals as the raised mint, minted by the fundraiser PDA
+    let receipt_bump = [receipt_bump.to_le()];
+    let seed = [
+        Seed::from(b"receipt"),
+        Seed::from(fundraiser.key()),
+        Seed::from(&receipt_bump),
+    ];
+
+    let seeds = Signer::from(&seed);
+    CreateAccount {
+        from: maker,
+        to: receipt_mint,
+        owner: token_program.key(),
+        space: Mint::LEN as u64,
+        lamports: Rent::get()?.minimum_balance(Mint::LEN),
+    }
+    .invoke_signed(&[seeds])?;
+
+    InitializeMint2 {
+        mint: receipt_mint,
+        decimals,
+        mint_authority: fundraiser.key(),
+        freeze_authority: None,
     }
     .invoke()?;
 
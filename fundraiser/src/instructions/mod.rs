@@ -1,21 +1,55 @@
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_token::instructions::Transfer;
 
+use crate::{constant::BPS_DENOMINATOR, error::FundraiserError, state::Fundraiser};
+
+pub mod cancel;
+pub mod claim;
 pub mod collect;
 pub mod contribute;
+pub mod crank_refunds;
+pub mod create_proposal;
+pub mod execute_proposal;
+pub mod finalize;
+pub mod flag;
 pub mod initialize;
 pub mod refund;
+pub mod vote;
 
+pub use cancel::*;
+pub use claim::*;
 pub use collect::*;
 pub use contribute::*;
+pub use crank_refunds::*;
+pub use create_proposal::*;
+pub use execute_proposal::*;
+pub use finalize::*;
+pub use flag::*;
 pub use initialize::*;
 use pinocchio_token::state::TokenAccount;
 pub use refund::*;
+pub use vote::*;
 
 pub enum FundInstructions {
     Initialize = 0,
     Contribute = 1,
     Refund = 2,
     Collect = 3,
+    CreateProposal = 4,
+    Vote = 5,
+    ExecuteProposal = 6,
+    Claim = 7,
+    Flag = 8,
+    Cancel = 9,
+    CrankRefunds = 10,
+    Finalize = 11,
 }
 
 impl TryFrom<&u8> for FundInstructions {
@@ -27,19 +61,99 @@ impl TryFrom<&u8> for FundInstructions {
             1 => Ok(FundInstructions::Contribute),
             2 => Ok(FundInstructions::Refund),
             3 => Ok(FundInstructions::Collect),
+            4 => Ok(FundInstructions::CreateProposal),
+            5 => Ok(FundInstructions::Vote),
+            6 => Ok(FundInstructions::ExecuteProposal),
+            7 => Ok(FundInstructions::Claim),
+            8 => Ok(FundInstructions::Flag),
+            9 => Ok(FundInstructions::Cancel),
+            10 => Ok(FundInstructions::CrankRefunds),
+            11 => Ok(FundInstructions::Finalize),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
 }
 
 pub fn validate_ata(ata: &AccountInfo, mint: &AccountInfo, owner: &AccountInfo) -> ProgramResult {
+    validate_token_account(ata, mint.key(), owner.key())
+}
+
+pub fn validate_token_account(ata: &AccountInfo, mint: &Pubkey, owner: &Pubkey) -> ProgramResult {
     let ata_state = TokenAccount::from_account_info(ata)?;
-    if mint.key() != ata_state.mint() {
-        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    if mint != ata_state.mint() {
+        return Err(FundraiserError::TokenAccountMismatch.into());
     }
-    if ata_state.owner() != owner.key() {
-        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    if ata_state.owner() != owner {
+        return Err(FundraiserError::TokenAccountMismatch.into());
     }
 
     Ok(())
 }
+
+/// Pays `amount` out of the campaign: SPL tokens from the vault ATA to `to_ata`,
+/// or, for native campaigns, lamports from the fundraiser PDA to `to_wallet`.
+pub fn pay_out(
+    fundraiser_state: &Fundraiser,
+    fundraiser: &AccountInfo,
+    vault: &AccountInfo,
+    to_ata: &AccountInfo,
+    to_wallet: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    if fundraiser_state.is_native() {
+        // the PDA has to stay rent exempt, only raised lamports can leave it
+        let remaining = fundraiser
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(FundraiserError::MathOverflow)?;
+        if remaining < Rent::get()?.minimum_balance(fundraiser.data_len()) {
+            return Err(FundraiserError::RentExemption.into());
+        }
+
+        *fundraiser.try_borrow_mut_lamports()? = remaining;
+        let mut to_lamports = to_wallet.try_borrow_mut_lamports()?;
+        *to_lamports = to_lamports
+            .checked_add(amount)
+            .ok_or(FundraiserError::MathOverflow)?;
+        return Ok(());
+    }
+
+    let maker = fundraiser_state.maker();
+    let bump = [fundraiser_state.bump.to_le()];
+    let seed = [
+        Seed::from(b"fundraiser"),
+        Seed::from(&maker),
+        Seed::from(&bump),
+    ];
+
+    let seeds = Signer::from(&seed);
+    Transfer {
+        from: vault,
+        to: to_ata,
+        amount,
+        authority: fundraiser,
+    }
+    .invoke_signed(&[seeds])
+}
+
+/// Closes a program-owned account, returning its rent to `destination`.
+pub fn close_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    {
+        let mut destination_lamports = destination.try_borrow_mut_lamports()?;
+        *destination_lamports = destination_lamports
+            .checked_add(account.lamports())
+            .ok_or(FundraiserError::MathOverflow)?;
+        *account.try_borrow_mut_lamports()? = 0;
+    }
+
+    account.close()
+}
+
+/// `amount * bps / 10_000`, computed in u128 so it can't overflow.
+pub fn apply_bps(amount: u64, bps: u16) -> Result<u64, ProgramError> {
+    let scaled = (amount as u128)
+        .checked_mul(bps as u128)
+        .and_then(|v| v.checked_div(BPS_DENOMINATOR as u128))
+        .ok_or(FundraiserError::MathOverflow)?;
+    u64::try_from(scaled).map_err(|_| FundraiserError::MathOverflow.into())
+}
@@ -1,18 +1,20 @@
 use pinocchio::{
     account_info::AccountInfo,
-    instruction::{Seed, Signer},
-    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
     ProgramResult,
 };
-use pinocchio_token::instructions::Transfer;
+use pinocchio_token::instructions::Burn;
+
+use pinocchio_account::PinocchioAccount;
 
 use crate::{
-    instructions::validate_ata,
-    state::{Contributor, Fundraiser},
+    error::FundraiserError,
+    instructions::{close_account, pay_out, validate_ata, validate_token_account},
+    state::{Contributor, Fundraiser, FundraiserStatus},
 };
 
 pub fn process_refund(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [contributor, contributor_ata, contributor_pda, mint, fundraiser, vault, _system_program, _token_program, _associated_token_program @ ..] =
+    let [contributor, contributor_ata, contributor_pda, mint, fundraiser, vault, receipt_mint, contributor_receipt_ata, _system_program, _token_program, _associated_token_program, accepted_accounts @ ..] =
         accounts
     else {
         return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
@@ -22,9 +24,11 @@ pub fn process_refund(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
         return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature);
     }
 
-    let fundraiser_data = fundraiser.try_borrow_data()?;
-    let fundraiser_state = bytemuck::try_pod_read_unaligned::<Fundraiser>(&fundraiser_data)
-        .expect("Invalid fundraiser data");
+    if data.is_empty() {
+        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    }
+
+    let fundraiser_state = *Fundraiser::load(fundraiser)?;
 
     if fundraiser_state.mint_to_raise.is_empty() {
         return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
@@ -37,47 +41,94 @@ pub fn process_refund(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
 
     let bump = data[0];
 
-    Contributor::validate_pda(bump, &contributor_pda.key(), &contributor.key())?;
-
-    validate_ata(contributor_ata, mint, contributor)?;
-    validate_ata(vault, mint, fundraiser)?;
+    Contributor::validate_pda(
+        bump,
+        &contributor_pda.key(),
+        &fundraiser.key(),
+        &contributor.key(),
+    )?;
 
-    // refund the contributor
-    let maker: Pubkey = fundraiser_state.maker();
-    let bump = [fundraiser_state.bump.to_le()];
-    let seed = [
-        Seed::from(b"fundraiser"),
-        Seed::from(&maker),
-        Seed::from(&bump),
-    ];
+    if !fundraiser_state.is_native() {
+        if mint.key() != &fundraiser_state.mint_to_raise() {
+            return Err(FundraiserError::InvalidAcceptedMints.into());
+        }
+        validate_ata(contributor_ata, mint, contributor)?;
+        validate_ata(vault, mint, fundraiser)?;
+    }
+    Fundraiser::validate_receipt_mint(
+        fundraiser_state.receipt_bump,
+        receipt_mint.key(),
+        fundraiser.key(),
+    )?;
 
-    let seeds = Signer::from(&seed);
+    // refunds wait for the campaign to fail: paying one out while it is still
+    // active would leave its pledge counted toward the target and the referrals.
+    // a succeeded campaign only opens refunds again once a rejected milestone or
+    // a flagged maker fails it; a cancelled campaign refunds right away
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    match fundraiser_state.status_at(current_time)? {
+        FundraiserStatus::Failed | FundraiserStatus::Cancelled => {}
+        FundraiserStatus::Active => return Err(FundraiserError::CampaignNotEnded.into()),
+        FundraiserStatus::Succeeded => return Err(FundraiserError::RefundsLocked.into()),
+        FundraiserStatus::Collected => return Err(FundraiserError::InvalidStatus.into()),
+    }
 
+    // refund the contributor
     {
-        let contributor_data = contributor_pda.try_borrow_data()?;
-        let contributor_state = bytemuck::try_pod_read_unaligned::<Contributor>(&contributor_data)
-            .expect("Invalid contributor data");
+        let contributor_state = *Contributor::load(contributor_pda)?;
 
-        Transfer {
-            from: vault,
-            to: contributor_ata,
+        // the receipts minted for the pledge have to be handed back before the refund
+        Burn {
+            account: contributor_receipt_ata,
+            mint: receipt_mint,
+            authority: contributor,
             amount: contributor_state.amount(),
-            authority: fundraiser,
         }
-        .invoke_signed(&[seeds])?;
+        .invoke()?;
+
+        // milestones and vesting are single-mint, so only the main vault is ever pro-rated
+        let amount = fundraiser_state.refund_amount(&contributor_state)?;
+
+        pay_out(
+            &fundraiser_state,
+            fundraiser,
+            vault,
+            contributor_ata,
+            contributor,
+            amount,
+        )?;
+
+        // accepted mints come back as deposited, one [vault, token account] pair each
+        let accepted_mints_count = fundraiser_state.accepted_mints_count() as usize;
+        if accepted_accounts.len() < accepted_mints_count * 2 {
+            return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+        }
+        for (i, pair) in accepted_accounts
+            .chunks_exact(2)
+            .take(accepted_mints_count)
+            .enumerate()
+        {
+            let deposit = contributor_state.deposit(i + 1);
+            if deposit == 0 {
+                continue;
+            }
+
+            let accepted_mint = fundraiser_state.accepted_mint(i);
+            validate_token_account(&pair[0], &accepted_mint, fundraiser.key())?;
+            validate_token_account(&pair[1], &accepted_mint, contributor.key())?;
+            pay_out(
+                &fundraiser_state,
+                fundraiser,
+                &pair[0],
+                &pair[1],
+                contributor,
+                deposit,
+            )?;
+        }
     }
 
     // close the contributor pda
-    let lamports = contributor_pda.lamports();
-    let mut contributor_lamports = contributor.try_borrow_mut_lamports()?;
-    *contributor_lamports += lamports;
-
-    {
-        let mut contributor_pda_lamports = contributor_pda.try_borrow_mut_lamports()?;
-        *contributor_pda_lamports -= lamports;
-    }
-
-    contributor_pda.close()?;
+    close_account(contributor_pda, contributor)?;
 
     Ok(())
 }
@@ -0,0 +1,106 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_account::PinocchioAccount;
+
+use crate::{
+    error::FundraiserError,
+    state::{Contributor, Fundraiser, FundraiserStatus, Proposal, Vote},
+};
+
+pub fn process_vote(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [voter, contributor_pda, fundraiser, proposal, vote, _system_program @ ..] = accounts
+    else {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    };
+
+    // checks:
+    if !voter.is_signer() {
+        return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature);
+    }
+
+    if contributor_pda.owner() != &crate::ID
+        || fundraiser.owner() != &crate::ID
+        || proposal.owner() != &crate::ID
+    {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountOwner);
+    }
+
+    if data.len() < 3 {
+        return Err(pinocchio::program_error::ProgramError::InvalidInstructionData);
+    }
+
+    let contributor_bump = data[0];
+    let vote_bump = data[1];
+    let approve = data[2] != 0;
+
+    // the contributor PDA is scoped to the fundraiser, so only its backers can vote
+    Contributor::validate_pda(
+        contributor_bump,
+        contributor_pda.key(),
+        fundraiser.key(),
+        voter.key(),
+    )?;
+
+    let mut proposal_state = Proposal::load_mut(proposal)?;
+    if proposal_state.fundraiser() != *fundraiser.key() {
+        return Err(pinocchio::program_error::ProgramError::InvalidAccountData);
+    }
+    if proposal_state.executed() {
+        return Err(FundraiserError::ProposalAlreadyExecuted.into());
+    }
+
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time >= proposal_state.voting_ends_at() {
+        return Err(FundraiserError::VotingClosed.into());
+    }
+    Fundraiser::load(fundraiser)?.require_status(current_time, &[FundraiserStatus::Succeeded])?;
+
+    let weight = Contributor::load(contributor_pda)?.amount();
+    if weight == 0 {
+        return Err(FundraiserError::NoVotingWeight.into());
+    }
+
+    Vote::validate_pda(vote_bump, vote.key(), proposal.key(), voter.key())?;
+
+    // the vote PDA doubles as the double-voting guard
+    if vote.owner() == &crate::ID {
+        return Err(FundraiserError::AlreadyVoted.into());
+    }
+
+    let bump = [vote_bump.to_le()];
+    let seed = [
+        Seed::from(b"vote"),
+        Seed::from(proposal.key()),
+        Seed::from(voter.key()),
+        Seed::from(&bump),
+    ];
+
+    let seeds = Signer::from(&seed);
+    let mut vote_state = Vote::init(vote, voter, &[seeds])?;
+    vote_state.set_voter(voter.key());
+    vote_state.set_weight(weight);
+    vote_state.set_approve(approve);
+    vote_state.bump = vote_bump;
+
+    if approve {
+        proposal_state.set_votes_for(
+            proposal_state
+                .votes_for()
+                .checked_add(weight)
+                .ok_or(FundraiserError::MathOverflow)?,
+        );
+    } else {
+        proposal_state.set_votes_against(
+            proposal_state
+                .votes_against()
+                .checked_add(weight)
+                .ok_or(FundraiserError::MathOverflow)?,
+        );
+    }
+
+    Ok(())
+}
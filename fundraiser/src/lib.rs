@@ -2,8 +2,11 @@ use pinocchio::{account_info::AccountInfo, entrypoint, msg, pubkey::Pubkey, Prog
 
 use crate::instructions::FundInstructions;
 
+mod allowlist;
 mod constant;
+mod error;
 mod instructions;
+mod oracle;
 mod state;
 mod tests;
 
@@ -28,6 +31,16 @@ pub fn process_instruction(
         FundInstructions::Contribute => instructions::process_contribute(accounts, data)?,
         FundInstructions::Refund => instructions::process_refund(accounts, data)?,
         FundInstructions::Collect => instructions::process_collect(accounts, data)?,
+        FundInstructions::CreateProposal => instructions::process_create_proposal(accounts, data)?,
+        FundInstructions::Vote => instructions::process_vote(accounts, data)?,
+        FundInstructions::ExecuteProposal => {
+            instructions::process_execute_proposal(accounts, data)?
+        }
+        FundInstructions::Claim => instructions::process_claim(accounts, data)?,
+        FundInstructions::Flag => instructions::process_flag(accounts, data)?,
+        FundInstructions::Cancel => instructions::process_cancel(accounts, data)?,
+        FundInstructions::CrankRefunds => instructions::process_crank_refunds(accounts, data)?,
+        FundInstructions::Finalize => instructions::process_finalize(accounts, data)?,
         _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
     }
     Ok(())
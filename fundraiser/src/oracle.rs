@@ -0,0 +1,69 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
+use switchboard_on_demand::on_demand::accounts::pull_feed::PullFeedAccountData;
+
+use crate::{
+    constant::{
+        MAX_PRICE_STALENESS_SLOTS, MIN_PRICE_SAMPLES, SWITCHBOARD_ON_DEMAND_DEVNET,
+        SWITCHBOARD_ON_DEMAND_MAINNET,
+    },
+    error::FundraiserError,
+};
+
+// anchor discriminator of PullFeedAccountData
+pub const PULL_FEED_DISCRIMINATOR: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+
+pub fn validate_price_feed(feed: &AccountInfo) -> Result<(), ProgramError> {
+    if feed.owner() != &SWITCHBOARD_ON_DEMAND_MAINNET
+        && feed.owner() != &SWITCHBOARD_ON_DEMAND_DEVNET
+    {
+        return Err(FundraiserError::InvalidPriceFeed.into());
+    }
+    Ok(())
+}
+
+/// Converts `amount` of a mint with `decimals` into the quote unit (`quote_decimals`),
+/// using the switchboard feed price of one whole token in whole quote tokens.
+pub fn quote_amount(
+    feed: &AccountInfo,
+    slot: u64,
+    amount: u64,
+    decimals: u8,
+    quote_decimals: u8,
+) -> Result<u64, ProgramError> {
+    validate_price_feed(feed)?;
+
+    let data = feed.try_borrow_data()?;
+    let len = core::mem::size_of::<PullFeedAccountData>();
+    if data.len() < 8 + len || data[..8] != PULL_FEED_DISCRIMINATOR {
+        return Err(FundraiserError::InvalidPriceFeed.into());
+    }
+    let feed_state = bytemuck::try_from_bytes::<PullFeedAccountData>(&data[8..8 + len])
+        .map_err(|_| FundraiserError::InvalidPriceFeed)?;
+
+    // same parameters as switchboard-example, but only positive prices make sense here
+    let price = feed_state
+        .get_value(slot, MAX_PRICE_STALENESS_SLOTS, MIN_PRICE_SAMPLES, true)
+        .map_err(|_| FundraiserError::StalePrice)?;
+
+    let mantissa = u128::try_from(price.mantissa()).map_err(|_| FundraiserError::StalePrice)?;
+    let scale = 10u128
+        .checked_pow(price.scale())
+        .ok_or(FundraiserError::MathOverflow)?;
+    let quoted = (amount as u128)
+        .checked_mul(mantissa)
+        .ok_or(FundraiserError::MathOverflow)?
+        / scale;
+
+    let quoted = if quote_decimals >= decimals {
+        10u128
+            .checked_pow((quote_decimals - decimals) as u32)
+            .and_then(|unit| quoted.checked_mul(unit))
+    } else {
+        10u128
+            .checked_pow((decimals - quote_decimals) as u32)
+            .map(|unit| quoted / unit)
+    }
+    .ok_or(FundraiserError::MathOverflow)?;
+
+    u64::try_from(quoted).map_err(|_| FundraiserError::MathOverflow.into())
+}
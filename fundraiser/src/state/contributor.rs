@@ -1,32 +1,41 @@
 use bytemuck::{Pod, Zeroable};
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use pinocchio_account::PinocchioAccount;
 use pinocchio_pubkey::derive_address;
 
+use crate::{constant::MAX_ACCEPTED_MINTS, error::FundraiserError};
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
 pub struct Contributor {
-    pub amount: [u8; 8],
+    pub amount: [u8; 8], // in the quote unit, across all accepted mints
+    pub flagged: u8,     // already flagged the vesting maker
+    pub deposits: [[u8; 8]; MAX_ACCEPTED_MINTS + 1], // raw amounts, mint_to_raise first
+    pub bump: u8,
 }
 
-impl Contributor {
-    pub const LEN: usize = core::mem::size_of::<Contributor>();
-
-    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
-        let mut data = account_info.try_borrow_mut_data()?;
-
-        if data.len() != Contributor::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-
-        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
-    }
+impl PinocchioAccount for Contributor {
+    const OWNER: Pubkey = crate::ID;
+    // sha256("account:Contributor")[..8]
+    const DISCRIMINATOR: &'static [u8] = &[222, 222, 255, 212, 133, 49, 27, 93];
+}
 
-    pub fn validate_pda(bump: u8, pda: &Pubkey, owner: &Pubkey) -> Result<(), ProgramError> {
-        let seeds = [b"contributor".as_ref(), owner.as_slice()];
+impl Contributor {
+    pub fn validate_pda(
+        bump: u8,
+        pda: &Pubkey,
+        fundraiser: &Pubkey,
+        owner: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        let seeds = [
+            b"contributor".as_ref(),
+            fundraiser.as_slice(),
+            owner.as_slice(),
+        ];
         let derived = derive_address(&seeds, Some(bump), &crate::ID);
 
         if derived != *pda {
-            return Err(ProgramError::InvalidAccountData);
+            return Err(FundraiserError::InvalidPda.into());
         }
         Ok(())
     }
@@ -35,7 +44,27 @@ impl Contributor {
         u64::from_le_bytes(self.amount)
     }
 
+    pub fn bump(&self) -> u8 {
+        self.bump
+    }
+
     pub fn set_amount(&mut self, amount: u64) {
         self.amount = amount.to_le_bytes();
     }
+
+    pub fn deposit(&self, mint_index: usize) -> u64 {
+        u64::from_le_bytes(self.deposits[mint_index])
+    }
+
+    pub fn set_deposit(&mut self, mint_index: usize, deposit: u64) {
+        self.deposits[mint_index] = deposit.to_le_bytes();
+    }
+
+    pub fn flagged(&self) -> bool {
+        self.flagged != 0
+    }
+
+    pub fn set_flagged(&mut self, flagged: bool) {
+        self.flagged = flagged as u8;
+    }
 }
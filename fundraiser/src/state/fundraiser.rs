@@ -1,7 +1,14 @@
 use bytemuck::{Pod, Zeroable};
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use pinocchio_account::PinocchioAccount;
 use pinocchio_pubkey::derive_address;
 
+use crate::{
+    constant::{MAX_ACCEPTED_MINTS, MAX_STRETCH_GOALS},
+    error::FundraiserError,
+    state::Contributor,
+};
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
 pub struct Fundraiser {
@@ -10,29 +17,93 @@ pub struct Fundraiser {
     pub amount_to_raise: [u8; 8],
     pub current_amount: [u8; 8],
     pub time_started: [u8; 8],
-    pub duration: [u8; 1], // in days
+    pub duration: [u8; 8], // in seconds
+    pub min_contribution: [u8; 8],
+    pub max_contribution_bps: [u8; 2], // max share of amount_to_raise per contributor
+    pub hard_cap: [u8; 8],             // 0 means uncapped
     pub bump: u8,
+    pub quorum_bps: [u8; 2],    // share of raised funds that must vote
+    pub threshold_bps: [u8; 2], // share of cast votes that must approve, 0 disables milestones
+    pub released_amount: [u8; 8],
+    pub proposal_count: [u8; 8],
+    pub stretch_goals: [[u8; 8]; MAX_STRETCH_GOALS], // ascending totals above amount_to_raise
+    pub stretch_goals_count: u8,
+    pub stretch_goals_reached: u8,
+    pub receipt_bump: u8, // receipt mint PDA, minted 1:1 with contributions
+    pub vesting_duration: [u8; 8], // seconds from end_time, 0 means collect all at once
+    pub vesting_cliff: [u8; 8], // seconds from end_time before anything vests
+    pub flagged_amount: [u8; 8], // contributions flagging the vesting maker
+    pub native: u8,       // raises lamports held by this PDA instead of an SPL mint
+    pub decimals: u8,     // of mint_to_raise, the quote unit of every amount
+    pub accepted_mints_count: u8,
+    pub accepted_mints: [[u8; 32]; MAX_ACCEPTED_MINTS], // besides mint_to_raise, one vault each
+    pub price_feeds: [[u8; 32]; MAX_ACCEPTED_MINTS], // switchboard feeds pricing them in mint_to_raise
+    pub status: u8,                                  // FundraiserStatus
+    pub allowlist_root: [u8; 32], // merkle root of allowed wallets, zero when unused
+    pub allowlist_authority: [u8; 32], // co-signs allowed contributions, zero when unused
+    pub referral_fee_bps: [u8; 2], // of referred contributions, paid to referrers at collect
+    pub referred_amount: [u8; 8], // contributions that came with a referrer
+    pub referrer_count: [u8; 2],  // referrer PDAs collect has to pay out
 }
 
-impl Fundraiser {
-    pub const LEN: usize = core::mem::size_of::<Fundraiser>();
+/// Allowed transitions:
+/// - `Active` → `Cancelled` through `Cancel`, before the end
+/// - `Active` → `Succeeded` / `Failed` after the end, persisted by `Finalize` or the first
+///   `Claim`, `CreateProposal` or `Flag`; other instructions only read it through `status_at`
+/// - `Succeeded` → `Failed` on a rejected milestone or a flagged maker
+/// - `Succeeded` → `Collected` once the whole raise went to the maker
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FundraiserStatus {
+    Active = 0,
+    Cancelled = 1,
+    Succeeded = 2,
+    Failed = 3,
+    Collected = 4,
+}
 
-    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
-        let mut data = account_info.try_borrow_mut_data()?;
+impl TryFrom<u8> for FundraiserStatus {
+    type Error = ProgramError;
 
-        if data.len() != Fundraiser::LEN {
-            return Err(ProgramError::InvalidAccountData);
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FundraiserStatus::Active),
+            1 => Ok(FundraiserStatus::Cancelled),
+            2 => Ok(FundraiserStatus::Succeeded),
+            3 => Ok(FundraiserStatus::Failed),
+            4 => Ok(FundraiserStatus::Collected),
+            _ => Err(ProgramError::InvalidAccountData),
         }
-
-        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
     }
+}
 
+impl PinocchioAccount for Fundraiser {
+    const OWNER: Pubkey = crate::ID;
+    // sha256("account:Fundraiser")[..8]
+    const DISCRIMINATOR: &'static [u8] = &[167, 106, 143, 202, 135, 131, 204, 196];
+}
+
+impl Fundraiser {
     pub fn validate_pda(bump: u8, pda: &Pubkey, maker: &Pubkey) -> Result<(), ProgramError> {
         let seeds = &[b"fundraiser".as_ref(), maker.as_slice()];
         let derived = derive_address(seeds, Some(bump), &crate::ID);
 
         if derived != *pda {
-            return Err(ProgramError::InvalidAccountData);
+            return Err(FundraiserError::InvalidPda.into());
+        }
+        Ok(())
+    }
+
+    pub fn validate_receipt_mint(
+        bump: u8,
+        pda: &Pubkey,
+        fundraiser: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        let seeds = &[b"receipt".as_ref(), fundraiser.as_slice()];
+        let derived = derive_address(seeds, Some(bump), &crate::ID);
+
+        if derived != *pda {
+            return Err(FundraiserError::InvalidPda.into());
         }
         Ok(())
     }
@@ -76,13 +147,44 @@ impl Fundraiser {
     pub fn set_time_started(&mut self, time_started: u64) {
         self.time_started = time_started.to_le_bytes();
     }
-    pub fn duration(&self) -> u8 {
-        self.duration[0]
+    pub fn duration(&self) -> u64 {
+        u64::from_le_bytes(self.duration)
+    }
+
+    pub fn set_duration(&mut self, duration: u64) {
+        self.duration = duration.to_le_bytes();
+    }
+
+    pub fn end_time(&self) -> Result<u64, ProgramError> {
+        self.time_started()
+            .checked_add(self.duration())
+            .ok_or(FundraiserError::MathOverflow.into())
     }
 
-    pub fn set_duration(&mut self, duration: u8) {
-        self.duration[0] = duration;
+    pub fn min_contribution(&self) -> u64 {
+        u64::from_le_bytes(self.min_contribution)
     }
+
+    pub fn set_min_contribution(&mut self, min_contribution: u64) {
+        self.min_contribution = min_contribution.to_le_bytes();
+    }
+
+    pub fn max_contribution_bps(&self) -> u16 {
+        u16::from_le_bytes(self.max_contribution_bps)
+    }
+
+    pub fn set_max_contribution_bps(&mut self, max_contribution_bps: u16) {
+        self.max_contribution_bps = max_contribution_bps.to_le_bytes();
+    }
+
+    pub fn hard_cap(&self) -> u64 {
+        u64::from_le_bytes(self.hard_cap)
+    }
+
+    pub fn set_hard_cap(&mut self, hard_cap: u64) {
+        self.hard_cap = hard_cap.to_le_bytes();
+    }
+
     pub fn bump(&self) -> u8 {
         self.bump
     }
@@ -90,4 +192,277 @@ impl Fundraiser {
     pub fn set_bump(&mut self, bump: u8) {
         self.bump = bump;
     }
+
+    pub fn quorum_bps(&self) -> u16 {
+        u16::from_le_bytes(self.quorum_bps)
+    }
+
+    pub fn set_quorum_bps(&mut self, quorum_bps: u16) {
+        self.quorum_bps = quorum_bps.to_le_bytes();
+    }
+
+    pub fn threshold_bps(&self) -> u16 {
+        u16::from_le_bytes(self.threshold_bps)
+    }
+
+    pub fn set_threshold_bps(&mut self, threshold_bps: u16) {
+        self.threshold_bps = threshold_bps.to_le_bytes();
+    }
+
+    pub fn milestones_enabled(&self) -> bool {
+        self.threshold_bps() != 0
+    }
+
+    pub fn released_amount(&self) -> u64 {
+        u64::from_le_bytes(self.released_amount)
+    }
+
+    pub fn set_released_amount(&mut self, released_amount: u64) {
+        self.released_amount = released_amount.to_le_bytes();
+    }
+
+    pub fn proposal_count(&self) -> u64 {
+        u64::from_le_bytes(self.proposal_count)
+    }
+
+    pub fn set_proposal_count(&mut self, proposal_count: u64) {
+        self.proposal_count = proposal_count.to_le_bytes();
+    }
+
+    /// A failed campaign refunds whatever is left, the status is the only record of it.
+    pub fn refunding(&self) -> bool {
+        self.status == FundraiserStatus::Failed as u8
+    }
+
+    pub fn stretch_goal(&self, index: usize) -> u64 {
+        u64::from_le_bytes(self.stretch_goals[index])
+    }
+
+    pub fn set_stretch_goal(&mut self, index: usize, stretch_goal: u64) {
+        self.stretch_goals[index] = stretch_goal.to_le_bytes();
+    }
+
+    pub fn stretch_goals_count(&self) -> u8 {
+        self.stretch_goals_count
+    }
+
+    pub fn set_stretch_goals_count(&mut self, stretch_goals_count: u8) {
+        self.stretch_goals_count = stretch_goals_count;
+    }
+
+    pub fn stretch_goals_reached(&self) -> u8 {
+        self.stretch_goals_reached
+    }
+
+    pub fn set_stretch_goals_reached(&mut self, stretch_goals_reached: u8) {
+        self.stretch_goals_reached = stretch_goals_reached;
+    }
+
+    pub fn vesting_duration(&self) -> u64 {
+        u64::from_le_bytes(self.vesting_duration)
+    }
+
+    pub fn set_vesting_duration(&mut self, vesting_duration: u64) {
+        self.vesting_duration = vesting_duration.to_le_bytes();
+    }
+
+    pub fn vesting_cliff(&self) -> u64 {
+        u64::from_le_bytes(self.vesting_cliff)
+    }
+
+    pub fn set_vesting_cliff(&mut self, vesting_cliff: u64) {
+        self.vesting_cliff = vesting_cliff.to_le_bytes();
+    }
+
+    pub fn vesting_enabled(&self) -> bool {
+        self.vesting_duration() != 0
+    }
+
+    /// Linear vesting of the raised amount from `end_time`, nothing before the cliff.
+    pub fn vested_amount(&self, current_time: u64) -> Result<u64, ProgramError> {
+        let elapsed = current_time.saturating_sub(self.end_time()?);
+        if elapsed < self.vesting_cliff() {
+            return Ok(0);
+        }
+        if elapsed >= self.vesting_duration() {
+            return Ok(self.current_amount());
+        }
+
+        let vested = (self.current_amount() as u128)
+            .checked_mul(elapsed as u128)
+            .and_then(|v| v.checked_div(self.vesting_duration() as u128))
+            .ok_or(FundraiserError::MathOverflow)?;
+        u64::try_from(vested).map_err(|_| FundraiserError::MathOverflow.into())
+    }
+
+    pub fn flagged_amount(&self) -> u64 {
+        u64::from_le_bytes(self.flagged_amount)
+    }
+
+    pub fn set_flagged_amount(&mut self, flagged_amount: u64) {
+        self.flagged_amount = flagged_amount.to_le_bytes();
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.native != 0
+    }
+
+    pub fn set_native(&mut self, native: bool) {
+        self.native = native as u8;
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    pub fn set_decimals(&mut self, decimals: u8) {
+        self.decimals = decimals;
+    }
+
+    pub fn accepted_mints_count(&self) -> u8 {
+        self.accepted_mints_count
+    }
+
+    pub fn set_accepted_mints_count(&mut self, accepted_mints_count: u8) {
+        self.accepted_mints_count = accepted_mints_count;
+    }
+
+    pub fn multi_mint(&self) -> bool {
+        self.accepted_mints_count != 0
+    }
+
+    pub fn accepted_mint(&self, index: usize) -> Pubkey {
+        Pubkey::from(self.accepted_mints[index])
+    }
+
+    pub fn set_accepted_mint(&mut self, index: usize, mint: &Pubkey) {
+        self.accepted_mints[index].copy_from_slice(mint.as_ref());
+    }
+
+    pub fn price_feed(&self, index: usize) -> Pubkey {
+        Pubkey::from(self.price_feeds[index])
+    }
+
+    pub fn set_price_feed(&mut self, index: usize, price_feed: &Pubkey) {
+        self.price_feeds[index].copy_from_slice(price_feed.as_ref());
+    }
+
+    pub fn status(&self) -> Result<FundraiserStatus, ProgramError> {
+        FundraiserStatus::try_from(self.status)
+    }
+
+    pub fn set_status(&mut self, status: FundraiserStatus) {
+        self.status = status as u8;
+    }
+
+    pub fn cancelled(&self) -> bool {
+        self.status == FundraiserStatus::Cancelled as u8
+    }
+
+    /// What a contributor gets back from the main vault: the deposit, or its pro-rata
+    /// share of what milestones and claims left behind once refunding.
+    pub fn refund_amount(&self, contributor: &Contributor) -> Result<u64, ProgramError> {
+        let amount = contributor.deposit(0);
+        if !self.refunding() {
+            return Ok(amount);
+        }
+
+        let remaining = self
+            .current_amount()
+            .checked_sub(self.released_amount())
+            .ok_or(FundraiserError::MathOverflow)?;
+        let share = (amount as u128)
+            .checked_mul(remaining as u128)
+            .and_then(|v| v.checked_div(self.current_amount() as u128))
+            .ok_or(FundraiserError::MathOverflow)?;
+        u64::try_from(share).map_err(|_| FundraiserError::MathOverflow.into())
+    }
+
+    pub fn allowlist_root(&self) -> [u8; 32] {
+        self.allowlist_root
+    }
+
+    pub fn set_allowlist_root(&mut self, root: &[u8; 32]) {
+        self.allowlist_root.copy_from_slice(root);
+    }
+
+    pub fn allowlist_authority(&self) -> Pubkey {
+        Pubkey::from(self.allowlist_authority)
+    }
+
+    pub fn set_allowlist_authority(&mut self, authority: &Pubkey) {
+        self.allowlist_authority.copy_from_slice(authority.as_ref());
+    }
+
+    pub fn allowlisted(&self) -> bool {
+        self.allowlist_root != [0; 32] || self.allowlist_authority != [0; 32]
+    }
+
+    pub fn referral_fee_bps(&self) -> u16 {
+        u16::from_le_bytes(self.referral_fee_bps)
+    }
+
+    pub fn set_referral_fee_bps(&mut self, referral_fee_bps: u16) {
+        self.referral_fee_bps = referral_fee_bps.to_le_bytes();
+    }
+
+    pub fn referred_amount(&self) -> u64 {
+        u64::from_le_bytes(self.referred_amount)
+    }
+
+    pub fn set_referred_amount(&mut self, referred_amount: u64) {
+        self.referred_amount = referred_amount.to_le_bytes();
+    }
+
+    pub fn referrer_count(&self) -> u16 {
+        u16::from_le_bytes(self.referrer_count)
+    }
+
+    pub fn set_referrer_count(&mut self, referrer_count: u16) {
+        self.referrer_count = referrer_count.to_le_bytes();
+    }
+
+    /// The stored status, with an ended `Active` campaign read as what `Finalize` settles it to.
+    pub fn status_at(&self, current_time: u64) -> Result<FundraiserStatus, ProgramError> {
+        let status = self.status()?;
+        if status != FundraiserStatus::Active || current_time < self.end_time()? {
+            return Ok(status);
+        }
+
+        if self.current_amount() >= self.amount_to_raise() {
+            Ok(FundraiserStatus::Succeeded)
+        } else {
+            Ok(FundraiserStatus::Failed)
+        }
+    }
+
+    /// Persists `status_at`, so an ended campaign leaves `Active` for good.
+    pub fn settle(&mut self, current_time: u64) -> Result<FundraiserStatus, ProgramError> {
+        let status = self.status_at(current_time)?;
+        self.set_status(status);
+        Ok(status)
+    }
+
+    /// Fails with `CampaignCancelled` or `InvalidStatus` unless the campaign is in one of `allowed`.
+    pub fn require_status(
+        &self,
+        current_time: u64,
+        allowed: &[FundraiserStatus],
+    ) -> Result<FundraiserStatus, ProgramError> {
+        let status = self.status_at(current_time)?;
+        if allowed.contains(&status) {
+            Ok(status)
+        } else if status == FundraiserStatus::Cancelled {
+            Err(FundraiserError::CampaignCancelled.into())
+        } else {
+            Err(FundraiserError::InvalidStatus.into())
+        }
+    }
+
+    /// Moves a succeeded campaign to `Collected` once nothing is left to release.
+    pub fn mark_collected_if_released(&mut self) {
+        if self.released_amount() >= self.current_amount() {
+            self.set_status(FundraiserStatus::Collected);
+        }
+    }
 }
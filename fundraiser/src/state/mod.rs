@@ -1,5 +1,11 @@
 pub mod contributor;
 pub mod fundraiser;
+pub mod proposal;
+pub mod referrer;
+pub mod vote;
 
 pub use contributor::*;
 pub use fundraiser::*;
+pub use proposal::*;
+pub use referrer::*;
+pub use vote::*;
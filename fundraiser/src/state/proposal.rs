@@ -0,0 +1,99 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use pinocchio_account::PinocchioAccount;
+use pinocchio_pubkey::derive_address;
+
+use crate::error::FundraiserError;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Proposal {
+    pub fundraiser: [u8; 32],
+    pub id: [u8; 8],
+    pub amount: [u8; 8], // milestone amount released to the maker if approved
+    pub votes_for: [u8; 8],
+    pub votes_against: [u8; 8],
+    pub voting_ends_at: [u8; 8],
+    pub executed: u8,
+    pub bump: u8,
+}
+
+impl PinocchioAccount for Proposal {
+    const OWNER: Pubkey = crate::ID;
+    // sha256("account:Proposal")[..8]
+    const DISCRIMINATOR: &'static [u8] = &[26, 94, 189, 187, 116, 136, 53, 33];
+}
+
+impl Proposal {
+    pub fn validate_pda(
+        bump: u8,
+        pda: &Pubkey,
+        fundraiser: &Pubkey,
+        id: u64,
+    ) -> Result<(), ProgramError> {
+        let id = id.to_le_bytes();
+        let seeds = [b"proposal".as_ref(), fundraiser.as_slice(), id.as_ref()];
+        let derived = derive_address(&seeds, Some(bump), &crate::ID);
+
+        if derived != *pda {
+            return Err(FundraiserError::InvalidPda.into());
+        }
+        Ok(())
+    }
+
+    pub fn fundraiser(&self) -> Pubkey {
+        Pubkey::from(self.fundraiser)
+    }
+
+    pub fn set_fundraiser(&mut self, fundraiser: &Pubkey) {
+        self.fundraiser.copy_from_slice(fundraiser.as_ref());
+    }
+
+    pub fn id(&self) -> u64 {
+        u64::from_le_bytes(self.id)
+    }
+
+    pub fn set_id(&mut self, id: u64) {
+        self.id = id.to_le_bytes();
+    }
+
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    pub fn set_amount(&mut self, amount: u64) {
+        self.amount = amount.to_le_bytes();
+    }
+
+    pub fn votes_for(&self) -> u64 {
+        u64::from_le_bytes(self.votes_for)
+    }
+
+    pub fn set_votes_for(&mut self, votes_for: u64) {
+        self.votes_for = votes_for.to_le_bytes();
+    }
+
+    pub fn votes_against(&self) -> u64 {
+        u64::from_le_bytes(self.votes_against)
+    }
+
+    pub fn set_votes_against(&mut self, votes_against: u64) {
+        self.votes_against = votes_against.to_le_bytes();
+    }
+
+    pub fn voting_ends_at(&self) -> u64 {
+        u64::from_le_bytes(self.voting_ends_at)
+    }
+
+    pub fn set_voting_ends_at(&mut self, voting_ends_at: u64) {
+        self.voting_ends_at = voting_ends_at.to_le_bytes();
+    }
+
+    pub fn executed(&self) -> bool {
+        self.executed != 0
+    }
+
+    pub fn set_executed(&mut self, executed: bool) {
+        self.executed = executed as u8;
+    }
+}
@@ -0,0 +1,70 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use pinocchio_account::PinocchioAccount;
+use pinocchio_pubkey::derive_address;
+
+use crate::error::FundraiserError;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Referrer {
+    pub referrer: [u8; 32], // wallet paid at collect
+    pub amount: [u8; 8],    // contributions referred, in the quote unit
+    pub paid: u8,           // referral fee already paid out
+    pub bump: u8,
+}
+
+impl PinocchioAccount for Referrer {
+    const OWNER: Pubkey = crate::ID;
+    // sha256("account:Referrer")[..8]
+    const DISCRIMINATOR: &'static [u8] = &[99, 150, 214, 66, 111, 120, 49, 126];
+}
+
+impl Referrer {
+    pub fn validate_pda(
+        bump: u8,
+        pda: &Pubkey,
+        fundraiser: &Pubkey,
+        referrer: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        let seeds = [
+            b"referrer".as_ref(),
+            fundraiser.as_slice(),
+            referrer.as_slice(),
+        ];
+        let derived = derive_address(&seeds, Some(bump), &crate::ID);
+
+        if derived != *pda {
+            return Err(FundraiserError::InvalidPda.into());
+        }
+        Ok(())
+    }
+
+    pub fn referrer(&self) -> Pubkey {
+        Pubkey::from(self.referrer)
+    }
+
+    pub fn set_referrer(&mut self, referrer: &Pubkey) {
+        self.referrer.copy_from_slice(referrer.as_ref());
+    }
+
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    pub fn set_amount(&mut self, amount: u64) {
+        self.amount = amount.to_le_bytes();
+    }
+
+    pub fn paid(&self) -> bool {
+        self.paid != 0
+    }
+
+    pub fn set_paid(&mut self, paid: bool) {
+        self.paid = paid as u8;
+    }
+
+    pub fn bump(&self) -> u8 {
+        self.bump
+    }
+}
@@ -0,0 +1,62 @@
+use bytemuck::{Pod, Zeroable};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use pinocchio_account::PinocchioAccount;
+use pinocchio_pubkey::derive_address;
+
+use crate::error::FundraiserError;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Vote {
+    pub voter: [u8; 32],
+    pub weight: [u8; 8],
+    pub approve: u8,
+    pub bump: u8,
+}
+
+impl PinocchioAccount for Vote {
+    const OWNER: Pubkey = crate::ID;
+    // sha256("account:Vote")[..8]
+    const DISCRIMINATOR: &'static [u8] = &[96, 91, 104, 57, 145, 35, 172, 155];
+}
+
+impl Vote {
+    pub fn validate_pda(
+        bump: u8,
+        pda: &Pubkey,
+        proposal: &Pubkey,
+        voter: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        let seeds = [b"vote".as_ref(), proposal.as_slice(), voter.as_slice()];
+        let derived = derive_address(&seeds, Some(bump), &crate::ID);
+
+        if derived != *pda {
+            return Err(FundraiserError::InvalidPda.into());
+        }
+        Ok(())
+    }
+
+    pub fn voter(&self) -> Pubkey {
+        Pubkey::from(self.voter)
+    }
+
+    pub fn set_voter(&mut self, voter: &Pubkey) {
+        self.voter.copy_from_slice(voter.as_ref());
+    }
+
+    pub fn weight(&self) -> u64 {
+        u64::from_le_bytes(self.weight)
+    }
+
+    pub fn set_weight(&mut self, weight: u64) {
+        self.weight = weight.to_le_bytes();
+    }
+
+    pub fn approve(&self) -> bool {
+        self.approve != 0
+    }
+
+    pub fn set_approve(&mut self, approve: bool) {
+        self.approve = approve as u8;
+    }
+}
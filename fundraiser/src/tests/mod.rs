@@ -3,7 +3,7 @@
 mod tests {
     use std::{path::PathBuf, vec};
 
-    use litesvm::LiteSVM;
+    use litesvm::{types::TransactionResult, LiteSVM};
     use litesvm_token::{
         spl_token::{
             self,
@@ -11,19 +11,28 @@ mod tests {
         },
         CreateAssociatedTokenAccount, CreateMint, MintTo,
     };
-    use solana_instruction::{AccountMeta, Instruction};
+    use solana_instruction::{error::InstructionError, AccountMeta, Instruction};
     use solana_keypair::Keypair;
     use solana_message::Message;
     use solana_native_token::LAMPORTS_PER_SOL;
+    use solana_nostd_sha256::hashv;
     use solana_pubkey::Pubkey;
     use solana_signer::Signer;
     use solana_transaction::Transaction;
+    use solana_transaction_error::TransactionError;
     use spl_associated_token_account::{
         get_associated_token_address,
         solana_program::{clock::Clock, program_pack::Pack},
     };
+    use switchboard_on_demand::on_demand::accounts::pull_feed::PullFeedAccountData;
 
-    use crate::constant::SECONDS_TO_DAYS;
+    use pinocchio_account::PinocchioAccount;
+
+    use crate::{
+        constant::{MAX_DURATION, SECONDS_TO_DAYS},
+        error::FundraiserError,
+        state::{Contributor, Fundraiser, FundraiserStatus, Proposal, Referrer, Vote},
+    };
 
     const PROGRAM_ID: &str = "BbFoDc7zsPk4QJLQmL6boWhc4HoGWbW8w4PPXGbdNfKL";
     const TOKEN_PROGRAM_ID: Pubkey = spl_token::ID;
@@ -106,6 +115,117 @@ mod tests {
         )
     }
 
+    pub struct InitParams {
+        amount_to_raise: u64,
+        min_contribution: u64,
+        max_contribution_bps: u16,
+        hard_cap: u64,
+        start_time: u64,
+        duration: u64,
+        quorum_bps: u16,
+        threshold_bps: u16,
+        stretch_goals: Vec<u64>,
+        vesting_duration: u64,
+        vesting_cliff: u64,
+        accepted_mints: Vec<(Pubkey, Pubkey)>, // (mint, switchboard feed)
+        allowlist_root: [u8; 32],
+        allowlist_authority: Pubkey,
+        referral_fee_bps: u16,
+    }
+
+    impl Default for InitParams {
+        fn default() -> Self {
+            Self {
+                amount_to_raise: 600 * 10u64.pow(6),
+                min_contribution: 10u64.pow(6),
+                max_contribution_bps: 10_000,
+                hard_cap: 0,   // uncapped
+                start_time: 0, // start right away
+                duration: SECONDS_TO_DAYS,
+                quorum_bps: 0,
+                threshold_bps: 0, // milestones disabled
+                stretch_goals: vec![],
+                vesting_duration: 0, // collect all at once
+                vesting_cliff: 0,
+                accepted_mints: vec![],
+                allowlist_root: [0; 32], // open to everyone
+                allowlist_authority: Pubkey::default(),
+                referral_fee_bps: 0, // no referral rewards
+            }
+        }
+    }
+
+    pub fn assert_fundraiser_error(result: TransactionResult, error: FundraiserError) {
+        let failed = result.expect_err("transaction should have failed");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::Custom(error as u32)),
+            "Logs: {}",
+            failed.meta.pretty_logs()
+        );
+    }
+
+    pub fn warp(svm: &mut LiteSVM, seconds: u64) {
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds as i64;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+    }
+
+    pub fn create_funded_contributor(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        mint: &Pubkey,
+        amount: u64,
+    ) -> (Keypair, Pubkey) {
+        let contributor = Keypair::new();
+        let contributor_ata = CreateAssociatedTokenAccount::new(svm, payer, mint)
+            .owner(&contributor.pubkey())
+            .send()
+            .expect("Failed to create contributor ata");
+
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        MintTo::new(svm, payer, mint, &contributor_ata, amount)
+            .send()
+            .expect("Failed to mint tokens to contributor");
+
+        (contributor, contributor_ata)
+    }
+
+    fn receipt_mint(fundraiser: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"receipt", fundraiser.as_ref()], &program_id())
+    }
+
+    // a switchboard pull feed holding one submission of `price`, 18 decimals, landed at `slot`
+    fn set_pull_feed(svm: &mut LiteSVM, feed: &Pubkey, slot: u64, price: i128) {
+        let mut feed_state: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        feed_state.submissions[0].slot = slot;
+        feed_state.submissions[0].value = price;
+
+        let mut data = crate::oracle::PULL_FEED_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&feed_state));
+        data[8..40].fill(1); // submissions[0].oracle
+        svm.set_account(
+            *feed,
+            solana_account::Account {
+                lamports: LAMPORTS_PER_SOL,
+                data,
+                owner: Pubkey::from(crate::constant::SWITCHBOARD_ON_DEMAND_MAINNET),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+    }
+
+    fn token_balance(svm: &LiteSVM, ata: &Pubkey) -> u64 {
+        spl_token::state::Account::unpack(&svm.get_account(ata).unwrap().data)
+            .unwrap()
+            .amount
+    }
+
     pub fn build_init_transaction(
         svm: &LiteSVM,
         payer: &Keypair,
@@ -115,34 +235,65 @@ mod tests {
         token_program: Pubkey,
         system_program: Pubkey,
         associated_token_program: Pubkey,
+        params: &InitParams,
     ) -> Transaction {
         let (fundraiser, bump) = Pubkey::find_program_address(
             &[b"fundraiser".as_ref(), payer.pubkey().as_ref()],
             &PROGRAM_ID.parse().unwrap(),
         );
-        let amount_to_raise: u64 = 600 * 10u64.pow(6);
 
-        let duration: u8 = 1;
+        let (receipt_mint, receipt_bump) = receipt_mint(&fundraiser);
+
         let init_data = [
             vec![0u8], // discriminator
             bump.to_le_bytes().to_vec(),
-            amount_to_raise.to_le_bytes().to_vec(),
-            duration.to_le_bytes().to_vec(),
+            params.amount_to_raise.to_le_bytes().to_vec(),
+            params.min_contribution.to_le_bytes().to_vec(),
+            params.max_contribution_bps.to_le_bytes().to_vec(),
+            params.hard_cap.to_le_bytes().to_vec(),
+            params.start_time.to_le_bytes().to_vec(),
+            params.duration.to_le_bytes().to_vec(),
+            params.quorum_bps.to_le_bytes().to_vec(),
+            params.threshold_bps.to_le_bytes().to_vec(),
+            vec![params.stretch_goals.len() as u8],
+            params
+                .stretch_goals
+                .iter()
+                .flat_map(|goal| goal.to_le_bytes())
+                .collect(),
+            vec![receipt_bump],
+            params.vesting_duration.to_le_bytes().to_vec(),
+            params.vesting_cliff.to_le_bytes().to_vec(),
+            vec![params.accepted_mints.len() as u8],
+            params.allowlist_root.to_vec(),
+            params.allowlist_authority.to_bytes().to_vec(),
+            params.referral_fee_bps.to_le_bytes().to_vec(),
         ]
         .concat();
 
+        let mut accounts = vec![
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(receipt_mint, false),
+            AccountMeta::new_readonly(system_program, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(associated_token_program, false),
+            AccountMeta::new_readonly(Rent::id(), false),
+        ];
+        for (accepted_mint, price_feed) in &params.accepted_mints {
+            accounts.push(AccountMeta::new_readonly(*accepted_mint, false));
+            accounts.push(AccountMeta::new_readonly(*price_feed, false));
+            accounts.push(AccountMeta::new(
+                get_associated_token_address(&fundraiser, accepted_mint),
+                false,
+            ));
+        }
+
         let init_ix = Instruction {
             program_id,
-            accounts: vec![
-                AccountMeta::new(payer.pubkey(), true),
-                AccountMeta::new_readonly(mint, false),
-                AccountMeta::new(fundraiser, false),
-                AccountMeta::new(vault, false),
-                AccountMeta::new_readonly(system_program, false),
-                AccountMeta::new_readonly(token_program, false),
-                AccountMeta::new_readonly(associated_token_program, false),
-                AccountMeta::new_readonly(Rent::id(), false),
-            ],
+            accounts,
             data: init_data,
         };
 
@@ -164,12 +315,6 @@ mod tests {
         system_program: Pubkey,
         associated_token_program: Pubkey,
     ) -> Transaction {
-        // Derive contributor PDA
-        let (contributor_pda, bump) = Pubkey::find_program_address(
-            &[b"contributor", contributor.pubkey().as_ref()],
-            &program_id,
-        );
-
         MintTo::new(
             &mut svm,
             contributor,
@@ -180,37 +325,244 @@ mod tests {
         .send()
         .unwrap();
 
+        build_contribute_only_transaction(
+            svm,
+            contributor,
+            amount,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        )
+    }
+
+    // same as build_contribute_transaction, for contributors that are not the mint authority
+    pub fn build_contribute_only_transaction(
+        svm: &LiteSVM,
+        contributor: &Keypair,
+        amount: u64,
+        mint: Pubkey,
+        contributor_ata: Pubkey,
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        program_id: Pubkey,
+        token_program: Pubkey,
+        system_program: Pubkey,
+        associated_token_program: Pubkey,
+    ) -> Transaction {
+        build_contribute_accepted_transaction(
+            svm,
+            contributor,
+            amount,
+            0,
+            None,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        )
+    }
+
+    // contributes one of the accepted mints, priced by its feed
+    pub fn build_contribute_accepted_transaction(
+        svm: &LiteSVM,
+        contributor: &Keypair,
+        amount: u64,
+        mint_index: u8,
+        price_feed: Option<Pubkey>,
+        mint: Pubkey,
+        contributor_ata: Pubkey,
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        program_id: Pubkey,
+        token_program: Pubkey,
+        system_program: Pubkey,
+        associated_token_program: Pubkey,
+    ) -> Transaction {
+        let contribute_ix = contribute_instruction(
+            &contributor.pubkey(),
+            amount,
+            mint_index,
+            price_feed,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+
+        let message = Message::new(&[contribute_ix], Some(&contributor.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[contributor], message, recent_blockhash)
+    }
+
+    // contributes mint_to_raise to an allowlisted campaign, with a merkle proof or the authority co-signing
+    pub fn build_contribute_allowlisted_transaction(
+        svm: &LiteSVM,
+        contributor: &Keypair,
+        amount: u64,
+        proof: &[[u8; 32]],
+        authority: Option<&Keypair>,
+        mint: Pubkey,
+        contributor_ata: Pubkey,
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        program_id: Pubkey,
+        token_program: Pubkey,
+        system_program: Pubkey,
+        associated_token_program: Pubkey,
+    ) -> Transaction {
+        let mut contribute_ix = contribute_instruction(
+            &contributor.pubkey(),
+            amount,
+            0,
+            None,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        // [11] = referred (0, no referrer), [12] = referrer bump, [13..] = merkle proof, 32 bytes per level
+        contribute_ix.data.extend([0, 0]);
+        contribute_ix.data.extend(proof.concat());
+
+        let mut signers = vec![contributor];
+        if let Some(authority) = authority {
+            contribute_ix
+                .accounts
+                .push(AccountMeta::new_readonly(authority.pubkey(), true)); // allowlist authority
+            signers.push(authority);
+        }
+
+        let message = Message::new(&[contribute_ix], Some(&contributor.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&signers, message, recent_blockhash)
+    }
+
+    // contributes mint_to_raise on behalf of a referrer
+    pub fn build_contribute_referred_transaction(
+        svm: &LiteSVM,
+        contributor: &Keypair,
+        amount: u64,
+        referrer: Pubkey,
+        mint: Pubkey,
+        contributor_ata: Pubkey,
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        program_id: Pubkey,
+        token_program: Pubkey,
+        system_program: Pubkey,
+        associated_token_program: Pubkey,
+    ) -> Transaction {
+        let mut contribute_ix = contribute_instruction(
+            &contributor.pubkey(),
+            amount,
+            0,
+            None,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        let (referrer_pda, referrer_bump) = referrer_pda(&fundraiser, &referrer);
+        // [11] = referred (1), [12] = referrer bump
+        contribute_ix.data.extend([1, referrer_bump]);
+        contribute_ix
+            .accounts
+            .push(AccountMeta::new_readonly(referrer, false)); // referrer wallet
+        contribute_ix
+            .accounts
+            .push(AccountMeta::new(referrer_pda, false)); // referrer PDA
+
+        let message = Message::new(&[contribute_ix], Some(&contributor.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[contributor], message, recent_blockhash)
+    }
+
+    pub fn referrer_pda(fundraiser: &Pubkey, referrer: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"referrer", fundraiser.as_ref(), referrer.as_ref()],
+            &program_id(),
+        )
+    }
+
+    pub fn contribute_instruction(
+        contributor: &Pubkey,
+        amount: u64,
+        mint_index: u8,
+        price_feed: Option<Pubkey>,
+        mint: Pubkey,
+        contributor_ata: Pubkey,
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        program_id: Pubkey,
+        token_program: Pubkey,
+        system_program: Pubkey,
+        associated_token_program: Pubkey,
+    ) -> Instruction {
+        // Derive contributor PDA
+        let (contributor_pda, bump) = Pubkey::find_program_address(
+            &[b"contributor", fundraiser.as_ref(), contributor.as_ref()],
+            &program_id,
+        );
+
+        let (receipt_mint, _) = receipt_mint(&fundraiser);
+        let receipt_ata = get_associated_token_address(contributor, &receipt_mint);
+
         // Instruction data layout:
         // [0] = discriminator (1 for contribute)
         // [1] = bump (u8)
         // [2..10] = amount (u64, LE)
+        // [10] = mint index (0 for mint_to_raise)
         let contribute_data = [
             vec![1u8],                     // discriminator for "Contribute"
             vec![bump],                    // bump byte
             amount.to_le_bytes().to_vec(), // contribution amount
+            vec![mint_index],              // mint index
         ]
         .concat();
 
-        // Build the contribute instruction
-        let contribute_ix = Instruction {
+        let mut accounts = vec![
+            AccountMeta::new(*contributor, true),     // contributor (signer)
+            AccountMeta::new(contributor_ata, false), // contributor's token account
+            AccountMeta::new(contributor_pda, false), // contributor PDA
+            AccountMeta::new_readonly(mint, false),   // mint
+            AccountMeta::new(fundraiser, false),      // fundraiser state
+            AccountMeta::new(vault, false),           // vault token account
+            AccountMeta::new(receipt_mint, false),    // receipt mint
+            AccountMeta::new(receipt_ata, false),     // contributor's receipt account
+            AccountMeta::new_readonly(system_program, false), // system program
+            AccountMeta::new_readonly(token_program, false), // token program
+            AccountMeta::new_readonly(associated_token_program, false), // associated token program
+        ];
+        if let Some(price_feed) = price_feed {
+            accounts.push(AccountMeta::new_readonly(price_feed, false)); // switchboard feed
+        }
+
+        Instruction {
             program_id,
-            accounts: vec![
-                AccountMeta::new(contributor.pubkey(), true), // contributor (signer)
-                AccountMeta::new(contributor_ata, false),     // contributor's token account
-                AccountMeta::new(contributor_pda, false),     // contributor PDA
-                AccountMeta::new_readonly(mint, false),       // mint
-                AccountMeta::new(fundraiser, false),          // fundraiser state
-                AccountMeta::new(vault, false),               // vault token account
-                AccountMeta::new_readonly(system_program, false), // system program
-                AccountMeta::new_readonly(token_program, false), // token program
-                AccountMeta::new_readonly(associated_token_program, false), // associated token program
-            ],
+            accounts,
             data: contribute_data,
-        };
-
-        let message = Message::new(&[contribute_ix], Some(&contributor.pubkey()));
-        let recent_blockhash = svm.latest_blockhash();
-        Transaction::new(&[contributor], message, recent_blockhash)
+        }
     }
 
     pub fn build_refund_transaction(
@@ -225,37 +577,66 @@ mod tests {
         system_program: Pubkey,
         associated_token_program: Pubkey,
     ) -> Transaction {
+        let refund_ix = refund_instruction(
+            &contributor.pubkey(),
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+
+        let message = Message::new(&[refund_ix], Some(&contributor.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[contributor], message, recent_blockhash)
+    }
+
+    pub fn refund_instruction(
+        contributor: &Pubkey,
+        mint: Pubkey,
+        contributor_ata: Pubkey,
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        program_id: Pubkey,
+        token_program: Pubkey,
+        system_program: Pubkey,
+        associated_token_program: Pubkey,
+    ) -> Instruction {
         // Derive contributor PDA (same as in process_refund)
         let (contributor_pda, bump) = Pubkey::find_program_address(
-            &[b"contributor", contributor.pubkey().as_ref()],
+            &[b"contributor", fundraiser.as_ref(), contributor.as_ref()],
             &program_id,
         );
 
+        let (receipt_mint, _) = receipt_mint(&fundraiser);
+        let receipt_ata = get_associated_token_address(contributor, &receipt_mint);
+
         // Instruction data layout:
         // [0] = discriminator (2 for refund)
         // [1] = bump (u8)
         let refund_data = [vec![2u8], vec![bump]].concat();
 
         // Build the refund instruction
-        let refund_ix = Instruction {
+        Instruction {
             program_id,
             accounts: vec![
-                AccountMeta::new(contributor.pubkey(), true), // contributor (signer)
-                AccountMeta::new(contributor_ata, false),     // contributor's ATA
-                AccountMeta::new(contributor_pda, false),     // contributor PDA
-                AccountMeta::new_readonly(mint, false),       // mint
-                AccountMeta::new(fundraiser, false),          // fundraiser state
-                AccountMeta::new(vault, false),               // vault
+                AccountMeta::new(*contributor, true),     // contributor (signer)
+                AccountMeta::new(contributor_ata, false), // contributor's ATA
+                AccountMeta::new(contributor_pda, false), // contributor PDA
+                AccountMeta::new_readonly(mint, false),   // mint
+                AccountMeta::new(fundraiser, false),      // fundraiser state
+                AccountMeta::new(vault, false),           // vault
+                AccountMeta::new(receipt_mint, false),    // receipt mint
+                AccountMeta::new(receipt_ata, false),     // contributor's receipt account
                 AccountMeta::new_readonly(system_program, false), // system program
                 AccountMeta::new_readonly(token_program, false), // token program
                 AccountMeta::new_readonly(associated_token_program, false), // associated token program
             ],
             data: refund_data,
-        };
-
-        let message = Message::new(&[refund_ix], Some(&contributor.pubkey()));
-        let recent_blockhash = svm.latest_blockhash();
-        Transaction::new(&[contributor], message, recent_blockhash)
+        }
     }
 
     pub fn build_collect_transaction(
@@ -268,6 +649,33 @@ mod tests {
         token_program: Pubkey,
         system_program: Pubkey,
         associated_token_program: Pubkey,
+    ) -> Transaction {
+        build_collect_with_accounts_transaction(
+            svm,
+            maker,
+            mint,
+            maker_ata,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            vec![],
+        )
+    }
+
+    // collect with trailing accounts, e.g. the [referrer PDA, referrer ATA] pairs
+    pub fn build_collect_with_accounts_transaction(
+        svm: &LiteSVM,
+        maker: &Keypair,
+        mint: Pubkey,
+        maker_ata: Pubkey,
+        vault: Pubkey,
+        program_id: Pubkey,
+        token_program: Pubkey,
+        system_program: Pubkey,
+        associated_token_program: Pubkey,
+        remaining: Vec<AccountMeta>,
     ) -> Transaction {
         // Derive fundraiser PDA (same seed pattern as process_collect)
         let (fundraiser_pda, bump) =
@@ -281,16 +689,20 @@ mod tests {
         // Build the collect instruction
         let collect_ix = Instruction {
             program_id,
-            accounts: vec![
-                AccountMeta::new(maker.pubkey(), true),  // maker (signer)
-                AccountMeta::new(maker_ata, false),      // maker's ATA
-                AccountMeta::new_readonly(mint, false),  // mint
-                AccountMeta::new(fundraiser_pda, false), // fundraiser PDA
-                AccountMeta::new(vault, false),          // vault
-                AccountMeta::new_readonly(system_program, false), // system program
-                AccountMeta::new_readonly(token_program, false), // token program
-                AccountMeta::new_readonly(associated_token_program, false), // associated token program
-            ],
+            accounts: [
+                vec![
+                    AccountMeta::new(maker.pubkey(), true),  // maker (signer)
+                    AccountMeta::new(maker_ata, false),      // maker's ATA
+                    AccountMeta::new_readonly(mint, false),  // mint
+                    AccountMeta::new(fundraiser_pda, false), // fundraiser PDA
+                    AccountMeta::new(vault, false),          // vault
+                    AccountMeta::new_readonly(system_program, false), // system program
+                    AccountMeta::new_readonly(token_program, false), // token program
+                    AccountMeta::new_readonly(associated_token_program, false), // associated token program
+                ],
+                remaining,
+            ]
+            .concat(),
             data: collect_data,
         };
 
@@ -299,12 +711,366 @@ mod tests {
         Transaction::new(&[maker], message, recent_blockhash)
     }
 
-    #[test]
-    pub fn test_init_instruction() {
-        let (
-            mut svm,
-            payer,
-            mint,
+    pub fn build_create_proposal_transaction(
+        svm: &LiteSVM,
+        maker: &Keypair,
+        fundraiser: Pubkey,
+        id: u64,
+        amount: u64,
+        voting_period: u64,
+        program_id: Pubkey,
+        system_program: Pubkey,
+    ) -> (Transaction, Pubkey) {
+        let (proposal, bump) = Pubkey::find_program_address(
+            &[b"proposal", fundraiser.as_ref(), id.to_le_bytes().as_ref()],
+            &program_id,
+        );
+
+        // Instruction data layout:
+        // [0] = discriminator (4 for CreateProposal)
+        // [1] = bump (u8)
+        // [2..10] = amount (u64, LE)
+        // [10..18] = voting period in seconds (u64, LE)
+        let proposal_data = [
+            vec![4u8],
+            vec![bump],
+            amount.to_le_bytes().to_vec(),
+            voting_period.to_le_bytes().to_vec(),
+        ]
+        .concat();
+
+        let proposal_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(maker.pubkey(), true), // maker (signer)
+                AccountMeta::new(fundraiser, false),    // fundraiser PDA
+                AccountMeta::new(proposal, false),      // proposal PDA
+                AccountMeta::new_readonly(system_program, false), // system program
+            ],
+            data: proposal_data,
+        };
+
+        let message = Message::new(&[proposal_ix], Some(&maker.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        (
+            Transaction::new(&[maker], message, recent_blockhash),
+            proposal,
+        )
+    }
+
+    pub fn build_vote_transaction(
+        svm: &LiteSVM,
+        voter: &Keypair,
+        fundraiser: Pubkey,
+        proposal: Pubkey,
+        approve: bool,
+        program_id: Pubkey,
+        system_program: Pubkey,
+    ) -> Transaction {
+        let (contributor_pda, contributor_bump) = Pubkey::find_program_address(
+            &[b"contributor", fundraiser.as_ref(), voter.pubkey().as_ref()],
+            &program_id,
+        );
+        let (vote, vote_bump) = Pubkey::find_program_address(
+            &[b"vote", proposal.as_ref(), voter.pubkey().as_ref()],
+            &program_id,
+        );
+
+        // Instruction data layout:
+        // [0] = discriminator (5 for Vote)
+        // [1] = contributor bump (u8)
+        // [2] = vote bump (u8)
+        // [3] = approve (u8, 1 = for, 0 = against)
+        let vote_data = vec![5u8, contributor_bump, vote_bump, approve as u8];
+
+        let vote_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(voter.pubkey(), true), // voter (signer)
+                AccountMeta::new_readonly(contributor_pda, false), // contributor PDA
+                AccountMeta::new_readonly(fundraiser, false), // fundraiser PDA
+                AccountMeta::new(proposal, false),      // proposal PDA
+                AccountMeta::new(vote, false),          // vote PDA
+                AccountMeta::new_readonly(system_program, false), // system program
+            ],
+            data: vote_data,
+        };
+
+        let message = Message::new(&[vote_ix], Some(&voter.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[voter], message, recent_blockhash)
+    }
+
+    pub fn build_execute_proposal_transaction(
+        svm: &LiteSVM,
+        payer: &Keypair,
+        maker: Pubkey,
+        maker_ata: Pubkey,
+        mint: Pubkey,
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        proposal: Pubkey,
+        program_id: Pubkey,
+        token_program: Pubkey,
+    ) -> Transaction {
+        // Instruction data layout:
+        // [0] = discriminator (6 for ExecuteProposal)
+        let execute_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(maker, false),         // maker
+                AccountMeta::new(maker_ata, false),              // maker's ATA
+                AccountMeta::new_readonly(mint, false),          // mint
+                AccountMeta::new(fundraiser, false),             // fundraiser PDA
+                AccountMeta::new(vault, false),                  // vault
+                AccountMeta::new(proposal, false),               // proposal PDA
+                AccountMeta::new_readonly(token_program, false), // token program
+            ],
+            data: vec![6u8],
+        };
+
+        let message = Message::new(&[execute_ix], Some(&payer.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[payer], message, recent_blockhash)
+    }
+
+    pub fn build_claim_transaction(
+        svm: &LiteSVM,
+        maker: &Keypair,
+        maker_ata: Pubkey,
+        mint: Pubkey,
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        program_id: Pubkey,
+        token_program: Pubkey,
+    ) -> Transaction {
+        // Instruction data layout:
+        // [0] = discriminator (7 for Claim)
+        let claim_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(maker.pubkey(), true), // maker (signer)
+                AccountMeta::new(maker_ata, false),     // maker's ATA
+                AccountMeta::new_readonly(mint, false), // mint
+                AccountMeta::new(fundraiser, false),    // fundraiser PDA
+                AccountMeta::new(vault, false),         // vault
+                AccountMeta::new_readonly(token_program, false), // token program
+            ],
+            data: vec![7u8],
+        };
+
+        let message = Message::new(&[claim_ix], Some(&maker.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[maker], message, recent_blockhash)
+    }
+
+    pub fn build_flag_transaction(
+        svm: &LiteSVM,
+        contributor: &Keypair,
+        fundraiser: Pubkey,
+        program_id: Pubkey,
+    ) -> Transaction {
+        let (contributor_pda, bump) = Pubkey::find_program_address(
+            &[
+                b"contributor",
+                fundraiser.as_ref(),
+                contributor.pubkey().as_ref(),
+            ],
+            &program_id,
+        );
+
+        // Instruction data layout:
+        // [0] = discriminator (8 for Flag)
+        // [1] = contributor bump (u8)
+        let flag_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true), // contributor (signer)
+                AccountMeta::new(contributor_pda, false),     // contributor PDA
+                AccountMeta::new(fundraiser, false),          // fundraiser PDA
+            ],
+            data: vec![8u8, bump],
+        };
+
+        let message = Message::new(&[flag_ix], Some(&contributor.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[contributor], message, recent_blockhash)
+    }
+
+    pub fn build_cancel_transaction(
+        svm: &LiteSVM,
+        maker: &Keypair,
+        fundraiser: Pubkey,
+        program_id: Pubkey,
+    ) -> Transaction {
+        // Instruction data layout:
+        // [0] = discriminator (9 for Cancel)
+        let cancel_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(maker.pubkey(), true), // maker (signer)
+                AccountMeta::new(fundraiser, false),    // fundraiser PDA
+            ],
+            data: vec![9u8],
+        };
+
+        let message = Message::new(&[cancel_ix], Some(&maker.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[maker], message, recent_blockhash)
+    }
+
+    // sorted-pair node of the allowlist merkle tree
+    pub fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            hashv(&[&a, &b])
+        } else {
+            hashv(&[&b, &a])
+        }
+    }
+
+    pub fn allowlist_leaf(wallet: &Pubkey) -> [u8; 32] {
+        hashv(&[b"allowlist", wallet.as_ref()])
+    }
+
+    pub fn build_finalize_transaction(
+        svm: &LiteSVM,
+        payer: &Keypair,
+        fundraiser: Pubkey,
+        program_id: Pubkey,
+    ) -> Transaction {
+        // Instruction data layout:
+        // [0] = discriminator (11 for Finalize)
+        let finalize_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(fundraiser, false), // fundraiser PDA
+            ],
+            data: vec![11u8],
+        };
+
+        let message = Message::new(&[finalize_ix], Some(&payer.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[payer], message, recent_blockhash)
+    }
+
+    /// `status` is the last byte of the fundraiser account.
+    pub fn fundraiser_status(svm: &LiteSVM, fundraiser: &Pubkey) -> u8 {
+        svm.get_account(fundraiser).unwrap().data
+            [Fundraiser::DISCRIMINATOR.len() + core::mem::offset_of!(Fundraiser, status)]
+    }
+
+    // overwrites the discriminator of a program account, forging another account type
+    pub fn retag(svm: &mut LiteSVM, account: &Pubkey, discriminator: &[u8]) {
+        let mut forged = svm.get_account(account).unwrap();
+        forged.data[..discriminator.len()].copy_from_slice(discriminator);
+        svm.set_account(*account, forged).unwrap();
+        svm.expire_blockhash();
+    }
+
+    pub fn assert_invalid_account_data(result: TransactionResult) {
+        let failed = result.expect_err("transaction should have failed");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::InvalidAccountData),
+            "Logs: {}",
+            failed.meta.pretty_logs()
+        );
+    }
+
+    pub fn build_crank_refunds_transaction(
+        svm: &LiteSVM,
+        cranker: &Keypair,
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        contributors: &[(Pubkey, Pubkey)],
+        program_id: Pubkey,
+        token_program: Pubkey,
+    ) -> Transaction {
+        let crank_ix =
+            crank_refunds_instruction(fundraiser, vault, contributors, program_id, token_program);
+
+        let message = Message::new(&[crank_ix], Some(&cranker.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[cranker], message, recent_blockhash)
+    }
+
+    pub fn crank_refunds_instruction(
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        contributors: &[(Pubkey, Pubkey)],
+        program_id: Pubkey,
+        token_program: Pubkey,
+    ) -> Instruction {
+        // Instruction data layout:
+        // [0] = discriminator (10 for CrankRefunds)
+        let (receipt_mint, _) = receipt_mint(&fundraiser);
+        let mut accounts = vec![
+            AccountMeta::new(fundraiser, false),             // fundraiser PDA
+            AccountMeta::new(vault, false),                  // vault
+            AccountMeta::new(receipt_mint, false),           // receipt mint
+            AccountMeta::new_readonly(token_program, false), // token program
+        ];
+        // one [contributor pda, contributor ata, contributor wallet, receipt ata] group each
+        for (wallet, ata) in contributors {
+            let (contributor_pda, _) = Pubkey::find_program_address(
+                &[b"contributor", fundraiser.as_ref(), wallet.as_ref()],
+                &program_id,
+            );
+            accounts.push(AccountMeta::new(contributor_pda, false));
+            accounts.push(AccountMeta::new(*ata, false));
+            accounts.push(AccountMeta::new(*wallet, false));
+            accounts.push(AccountMeta::new(
+                get_associated_token_address(wallet, &receipt_mint),
+                false,
+            ));
+        }
+
+        Instruction {
+            program_id,
+            accounts,
+            data: vec![10u8],
+        }
+    }
+
+    // lets the fundraiser PDA burn the contributor's receipts when cranking their refund
+    pub fn build_approve_receipts_transaction(
+        svm: &LiteSVM,
+        contributor: &Keypair,
+        fundraiser: Pubkey,
+        amount: u64,
+    ) -> Transaction {
+        let (receipt_mint, _) = receipt_mint(&fundraiser);
+
+        // Instruction data layout:
+        // [0] = discriminator (4 for spl-token Approve)
+        // [1..9] = amount (u64, little-endian)
+        let mut approve_data = vec![4u8];
+        approve_data.extend_from_slice(&amount.to_le_bytes());
+
+        let approve_ix = Instruction {
+            program_id: TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(
+                    get_associated_token_address(&contributor.pubkey(), &receipt_mint),
+                    false,
+                ), // receipt ata
+                AccountMeta::new_readonly(fundraiser, false), // delegate
+                AccountMeta::new_readonly(contributor.pubkey(), true), // owner
+            ],
+            data: approve_data,
+        };
+
+        let message = Message::new(&[approve_ix], Some(&contributor.pubkey()));
+        let recent_blockhash = svm.latest_blockhash();
+        Transaction::new(&[contributor], message, recent_blockhash)
+    }
+
+    #[test]
+    pub fn test_init_instruction() {
+        let (
+            mut svm,
+            payer,
+            mint,
             _contributor_ata,
             _fundraiser,
             vault,
@@ -326,6 +1092,7 @@ mod tests {
             token_program,
             system_program,
             associated_token_program,
+            &InitParams::default(),
         );
 
         let tx = svm
@@ -363,6 +1130,7 @@ mod tests {
             token_program,
             system_program,
             associated_token_program,
+            &InitParams::default(),
         );
 
         let _tx = svm
@@ -394,6 +1162,20 @@ mod tests {
         msg!("Amount deposited by contributor: {}", amount);
         assert_eq!(vault_data.amount, amount);
 
+        // receipts are minted 1:1 by the fundraiser PDA
+        let (receipt_mint, _) = receipt_mint(&fundraiser);
+        let receipt_mint_data =
+            spl_token::state::Mint::unpack(&svm.get_account(&receipt_mint).unwrap().data).unwrap();
+        assert_eq!(receipt_mint_data.mint_authority, Some(fundraiser).into());
+        assert_eq!(receipt_mint_data.supply, amount);
+        assert_eq!(
+            token_balance(
+                &svm,
+                &get_associated_token_address(&payer.pubkey(), &receipt_mint)
+            ),
+            amount
+        );
+
         msg!("\n\n Contribute transaction sucessfull");
         msg!("Logs: {}", tx.pretty_logs());
         msg!("CUs Consumed: {}", tx.compute_units_consumed);
@@ -424,6 +1206,7 @@ mod tests {
             token_program,
             system_program,
             associated_token_program,
+            &InitParams::default(),
         );
 
         let _tx1 = svm
@@ -450,30 +1233,50 @@ mod tests {
             .send_transaction(transaction2)
             .expect("Failed to send contribute tx");
 
-        let transaction = build_refund_transaction(
-            &svm,
-            &payer,
-            mint,
-            contributor_ata,
-            fundraiser,
-            vault,
-            program_id,
-            token_program,
-            system_program,
-            associated_token_program,
+        let refund = |svm: &LiteSVM| {
+            build_refund_transaction(
+                svm,
+                &payer,
+                mint,
+                contributor_ata,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            )
+        };
+
+        // the campaign is still running, refunds wait for it to fail
+        assert_fundraiser_error(
+            svm.send_transaction(refund(&svm)),
+            FundraiserError::CampaignNotEnded,
         );
 
+        // time travel 2 days into future, the target was missed
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+
         let tx = svm
-            .send_transaction(transaction)
+            .send_transaction(refund(&svm))
             .expect("Failed to send refund tx");
 
+        let (receipt_mint, _) = receipt_mint(&fundraiser);
+        assert_eq!(
+            token_balance(
+                &svm,
+                &get_associated_token_address(&payer.pubkey(), &receipt_mint)
+            ),
+            0
+        );
+
         msg!("\n\n Refund transaction sucessfull");
         msg!("Logs: {}", tx.pretty_logs());
         msg!("CUs Consumed: {}", tx.compute_units_consumed);
     }
 
     #[test]
-    pub fn test_collect_instruction() {
+    pub fn test_refund_rejected_while_active() {
         let (
             mut svm,
             payer,
@@ -489,7 +1292,7 @@ mod tests {
         let program_id = program_id();
         let maker_ata = get_associated_token_address(&payer.pubkey(), &mint);
 
-        let transaction1 = build_init_transaction(
+        let transaction = build_init_transaction(
             &svm,
             &payer,
             mint,
@@ -498,18 +1301,15 @@ mod tests {
             token_program,
             system_program,
             associated_token_program,
+            &InitParams::default(),
         );
-
-        let _tx1 = svm
-            .send_transaction(transaction1)
+        svm.send_transaction(transaction)
             .expect("Failed to send init tx");
 
-        let amount: u64 = 400_000_000; // just enough
-
-        let transaction2 = build_contribute_transaction(
+        let transaction = build_contribute_transaction(
             &mut svm,
             &payer,
-            amount,
+            300 * 10u64.pow(6),
             mint,
             contributor_ata,
             fundraiser,
@@ -519,59 +1319,130 @@ mod tests {
             system_program,
             associated_token_program,
         );
-        let _tx2 = svm
-            .send_transaction(transaction2)
+        svm.send_transaction(transaction)
             .expect("Failed to send contribute tx");
 
-        let contributor2 = Keypair::new();
-        let contributor2_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint)
-            .owner(&contributor2.pubkey())
-            .send()
-            .expect("Failed to create ata for contributor 2!");
-
-        svm.airdrop(&contributor2.pubkey(), 10 * LAMPORTS_PER_SOL)
-            .unwrap();
-
-        MintTo::new(&mut svm, &payer, &mint, &contributor2_ata, amount)
-            .send()
-            .expect("Failed to mint tokens to contributor 2!");
+        let refund = build_refund_transaction(
+            &svm,
+            &payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(refund),
+            FundraiserError::CampaignNotEnded,
+        );
+        assert_eq!(token_balance(&svm, &vault), 300 * 10u64.pow(6));
 
-        let (contributor_pda, bump) = Pubkey::find_program_address(
-            &[b"contributor", contributor2.pubkey().as_ref()],
-            &program_id,
+        // contributing again reaches the target with tokens that are all in the vault
+        svm.expire_blockhash();
+        let transaction = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            300 * 10u64.pow(6),
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
         );
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+        assert_eq!(token_balance(&svm, &vault), 600 * 10u64.pow(6));
 
-        // Instruction data layout:
-        // [0] = discriminator (1 for contribute)
-        // [1] = bump (u8)
-        // [2..10] = amount (u64, LE)
-        let contribute_data = [
-            vec![1u8],                     // discriminator for "Contribute"
-            vec![bump],                    // bump byte
-            amount.to_le_bytes().to_vec(), // contribution amount
-        ]
-        .concat();
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+        let collect = build_collect_transaction(
+            &svm,
+            &payer,
+            mint,
+            maker_ata,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(collect)
+            .expect("Failed to send collect tx");
+        assert_eq!(token_balance(&svm, &vault), 0);
+    }
+
+    #[test]
+    pub fn test_collect_instruction() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+        let maker_ata = get_associated_token_address(&payer.pubkey(), &mint);
 
-        // Build the contribute instruction
-        let contribute_ix = Instruction {
+        let transaction1 = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
             program_id,
-            accounts: vec![
-                AccountMeta::new(contributor2.pubkey(), true), // contributor (signer)
-                AccountMeta::new(contributor2_ata, false),     // contributor's token account
-                AccountMeta::new(contributor_pda, false),      // contributor PDA
-                AccountMeta::new_readonly(mint, false),        // mint
-                AccountMeta::new(fundraiser, false),           // fundraiser state
-                AccountMeta::new(vault, false),                // vault token account
-                AccountMeta::new_readonly(system_program, false), // system program
-                AccountMeta::new_readonly(token_program, false), // token program
-                AccountMeta::new_readonly(associated_token_program, false), // associated token program
-            ],
-            data: contribute_data,
-        };
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams::default(),
+        );
 
-        let message = Message::new(&[contribute_ix], Some(&contributor2.pubkey()));
-        let recent_blockhash = svm.latest_blockhash();
-        let transaction3 = Transaction::new(&[contributor2], message, recent_blockhash);
+        let _tx1 = svm
+            .send_transaction(transaction1)
+            .expect("Failed to send init tx");
+
+        let amount: u64 = 400_000_000; // just enough
+
+        let transaction2 = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            amount,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        let _tx2 = svm
+            .send_transaction(transaction2)
+            .expect("Failed to send contribute tx");
+
+        let (contributor2, contributor2_ata) =
+            create_funded_contributor(&mut svm, &payer, &mint, amount);
+        let transaction3 = build_contribute_only_transaction(
+            &svm,
+            &contributor2,
+            amount,
+            mint,
+            contributor2_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
         let _tx3 = svm
             .send_transaction(transaction3)
             .expect("Failed to send contribute 2 tx");
@@ -602,4 +1473,2429 @@ mod tests {
         msg!("Logs: {}", tx.pretty_logs());
         msg!("CUs Consumed: {}", tx.compute_units_consumed);
     }
+
+    #[test]
+    pub fn test_milestone_proposal_instructions() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+        let maker_ata = get_associated_token_address(&payer.pubkey(), &mint);
+
+        // 50% quorum, 60% approval threshold
+        let transaction1 = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                quorum_bps: 5_000,
+                threshold_bps: 6_000,
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction1)
+            .expect("Failed to send init tx");
+
+        let amount: u64 = 400_000_000;
+
+        let transaction2 = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            amount,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction2)
+            .expect("Failed to send contribute tx");
+
+        let (contributor2, contributor2_ata) =
+            create_funded_contributor(&mut svm, &payer, &mint, amount);
+        let transaction3 = build_contribute_only_transaction(
+            &svm,
+            &contributor2,
+            amount,
+            mint,
+            contributor2_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction3)
+            .expect("Failed to send contribute 2 tx");
+
+        // campaign succeeded, move past its 1 day duration
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 2 * SECONDS_TO_DAYS as i64;
+        svm.set_sysvar::<Clock>(&clock);
+
+        // collect is disabled for milestone campaigns
+        let collect = build_collect_transaction(
+            &svm,
+            &payer,
+            mint,
+            maker_ata,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(collect),
+            FundraiserError::MilestonesEnabled,
+        );
+
+        // milestone 0: only the maker's 50% votes, all in favour -> released
+        let (transaction4, proposal0) = build_create_proposal_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            0,
+            300_000_000,
+            SECONDS_TO_DAYS,
+            program_id,
+            system_program,
+        );
+        svm.send_transaction(transaction4)
+            .expect("Failed to send create proposal tx");
+
+        let transaction5 = build_vote_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            proposal0,
+            true,
+            program_id,
+            system_program,
+        );
+        svm.send_transaction(transaction5)
+            .expect("Failed to send vote tx");
+
+        // voting twice on the same proposal is rejected
+        svm.expire_blockhash();
+        let transaction6 = build_vote_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            proposal0,
+            true,
+            program_id,
+            system_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(transaction6),
+            FundraiserError::AlreadyVoted,
+        );
+
+        let maker_before =
+            spl_token::state::Account::unpack(&svm.get_account(&maker_ata).unwrap().data)
+                .unwrap()
+                .amount;
+
+        clock.unix_timestamp += 2 * SECONDS_TO_DAYS as i64;
+        svm.set_sysvar::<Clock>(&clock);
+
+        let transaction7 = build_execute_proposal_transaction(
+            &svm,
+            &payer,
+            payer.pubkey(),
+            maker_ata,
+            mint,
+            fundraiser,
+            vault,
+            proposal0,
+            program_id,
+            token_program,
+        );
+        svm.send_transaction(transaction7)
+            .expect("Failed to send execute proposal tx");
+
+        let maker_after =
+            spl_token::state::Account::unpack(&svm.get_account(&maker_ata).unwrap().data)
+                .unwrap()
+                .amount;
+        assert_eq!(maker_after - maker_before, 300_000_000);
+
+        // milestone 1: contributor 2 votes against -> rejected, refunds open
+        let (transaction8, proposal1) = build_create_proposal_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            1,
+            200_000_000,
+            SECONDS_TO_DAYS,
+            program_id,
+            system_program,
+        );
+        svm.send_transaction(transaction8)
+            .expect("Failed to send create proposal 2 tx");
+
+        let transaction9 = build_vote_transaction(
+            &svm,
+            &contributor2,
+            fundraiser,
+            proposal1,
+            false,
+            program_id,
+            system_program,
+        );
+        svm.send_transaction(transaction9)
+            .expect("Failed to send vote 2 tx");
+
+        clock.unix_timestamp += 2 * SECONDS_TO_DAYS as i64;
+        svm.set_sysvar::<Clock>(&clock);
+
+        let transaction10 = build_execute_proposal_transaction(
+            &svm,
+            &payer,
+            payer.pubkey(),
+            maker_ata,
+            mint,
+            fundraiser,
+            vault,
+            proposal1,
+            program_id,
+            token_program,
+        );
+        svm.send_transaction(transaction10)
+            .expect("Failed to send execute proposal 2 tx");
+
+        // contributor 2 gets their share of the 500 left in the vault
+        let transaction11 = build_refund_transaction(
+            &svm,
+            &contributor2,
+            mint,
+            contributor2_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        let tx = svm
+            .send_transaction(transaction11)
+            .expect("Failed to send refund tx");
+
+        let contributor2_balance =
+            spl_token::state::Account::unpack(&svm.get_account(&contributor2_ata).unwrap().data)
+                .unwrap()
+                .amount;
+        assert_eq!(contributor2_balance, 250_000_000);
+
+        msg!("\n\n Milestone proposal transactions sucessfull");
+        msg!("Logs: {}", tx.pretty_logs());
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+    }
+
+    #[test]
+    pub fn test_overlapping_proposals() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+        let maker_ata = get_associated_token_address(&payer.pubkey(), &mint);
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                quorum_bps: 5_000,
+                threshold_bps: 6_000,
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let transaction = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            600 * 10u64.pow(6),
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+
+        // both fit on their own, together they ask for more than was raised
+        let mut proposals = vec![];
+        for id in 0..2 {
+            let (transaction, proposal) = build_create_proposal_transaction(
+                &svm,
+                &payer,
+                fundraiser,
+                id,
+                400 * 10u64.pow(6),
+                SECONDS_TO_DAYS,
+                program_id,
+                system_program,
+            );
+            svm.send_transaction(transaction)
+                .expect("Failed to send create proposal tx");
+            svm.send_transaction(build_vote_transaction(
+                &svm,
+                &payer,
+                fundraiser,
+                proposal,
+                true,
+                program_id,
+                system_program,
+            ))
+            .expect("Failed to send vote tx");
+            proposals.push(proposal);
+        }
+
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+        for proposal in proposals {
+            svm.send_transaction(build_execute_proposal_transaction(
+                &svm,
+                &payer,
+                payer.pubkey(),
+                maker_ata,
+                mint,
+                fundraiser,
+                vault,
+                proposal,
+                program_id,
+                token_program,
+            ))
+            .expect("Failed to send execute proposal tx");
+        }
+
+        // the second approval no longer fits: nothing moves and refunds stay closed
+        assert_eq!(token_balance(&svm, &vault), 200 * 10u64.pow(6));
+        assert_eq!(
+            fundraiser_status(&svm, &fundraiser),
+            FundraiserStatus::Succeeded as u8
+        );
+        let fundraiser_state = bytemuck::pod_read_unaligned::<Fundraiser>(
+            &svm.get_account(&fundraiser).unwrap().data[Fundraiser::DISCRIMINATOR.len()..],
+        );
+        assert!(!fundraiser_state.refunding());
+        assert_eq!(fundraiser_state.released_amount(), 400 * 10u64.pow(6));
+    }
+
+    #[test]
+    pub fn test_initialize_rejections() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            _contributor_ata,
+            _fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+
+        // give the clock a non-zero "now" so a past start time exists
+        warp(&mut svm, 10 * SECONDS_TO_DAYS);
+
+        let cases = vec![
+            (
+                InitParams {
+                    amount_to_raise: 49 * 10u64.pow(6),
+                    ..InitParams::default()
+                },
+                FundraiserError::TargetTooLow,
+            ),
+            (
+                InitParams {
+                    min_contribution: 0,
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidMinContribution,
+            ),
+            (
+                InitParams {
+                    max_contribution_bps: 10_001,
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidMaxContribution,
+            ),
+            (
+                InitParams {
+                    hard_cap: 500 * 10u64.pow(6),
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidHardCap,
+            ),
+            (
+                InitParams {
+                    duration: MAX_DURATION + 1,
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidDuration,
+            ),
+            (
+                InitParams {
+                    start_time: 1,
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidStartTime,
+            ),
+            (
+                InitParams {
+                    quorum_bps: 10_001,
+                    threshold_bps: 5_000,
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidBasisPoints,
+            ),
+            (
+                InitParams {
+                    stretch_goals: vec![700 * 10u64.pow(6), 650 * 10u64.pow(6)],
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidStretchGoals,
+            ),
+            (
+                InitParams {
+                    hard_cap: 650 * 10u64.pow(6),
+                    stretch_goals: vec![700 * 10u64.pow(6)],
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidStretchGoals,
+            ),
+            (
+                InitParams {
+                    quorum_bps: 5_000,
+                    vesting_duration: SECONDS_TO_DAYS,
+                    vesting_cliff: 2 * SECONDS_TO_DAYS,
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidVesting,
+            ),
+            (
+                InitParams {
+                    vesting_duration: SECONDS_TO_DAYS,
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidVesting,
+            ),
+            (
+                InitParams {
+                    allowlist_root: [1; 32],
+                    allowlist_authority: Pubkey::new_unique(),
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidAllowlist,
+            ),
+        ];
+
+        for (params, error) in cases {
+            let transaction = build_init_transaction(
+                &svm,
+                &payer,
+                mint,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+                &params,
+            );
+            assert_fundraiser_error(svm.send_transaction(transaction), error);
+        }
+    }
+
+    #[test]
+    pub fn test_contribute_rejections() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+
+        // opens in a day, 90% max share (540), hard cap at the 600 target
+        let start_time = svm.get_sysvar::<Clock>().unix_timestamp as u64 + SECONDS_TO_DAYS;
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                max_contribution_bps: 9_000,
+                hard_cap: 600 * 10u64.pow(6),
+                start_time,
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let cases = vec![
+            (1_000_000, Some(FundraiserError::CampaignNotStarted)),
+            (999_999, Some(FundraiserError::ContributionTooSmall)),
+            (540_000_001, Some(FundraiserError::ContributionTooLarge)),
+            (500_000_000, None),
+        ];
+
+        for (i, (amount, error)) in cases.into_iter().enumerate() {
+            if i == 1 {
+                warp(&mut svm, SECONDS_TO_DAYS);
+            }
+            let transaction = build_contribute_transaction(
+                &mut svm,
+                &payer,
+                amount,
+                mint,
+                contributor_ata,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            );
+            let result = svm.send_transaction(transaction);
+            match error {
+                Some(error) => assert_fundraiser_error(result, error),
+                None => {
+                    result.expect("Failed to send contribute tx");
+                }
+            }
+        }
+
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+
+        let transaction = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            1_000_000,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(transaction),
+            FundraiserError::CampaignEnded,
+        );
+    }
+
+    #[test]
+    pub fn test_stretch_goals_and_hard_cap() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                hard_cap: 750 * 10u64.pow(6),
+                stretch_goals: vec![650 * 10u64.pow(6), 700 * 10u64.pow(6)],
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let transaction = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            600 * 10u64.pow(6),
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+
+        let (contributor2, contributor2_ata) =
+            create_funded_contributor(&mut svm, &payer, &mint, 300 * 10u64.pow(6));
+
+        // 660 crosses the first stretch goal
+        let transaction = build_contribute_only_transaction(
+            &svm,
+            &contributor2,
+            60 * 10u64.pow(6),
+            mint,
+            contributor2_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        let tx = svm
+            .send_transaction(transaction)
+            .expect("Failed to send contribute 2 tx");
+        assert!(tx
+            .logs
+            .iter()
+            .any(|log| log.contains("Stretch goal 0 reached")));
+
+        // 200 more only fits 90 under the cap, the rest stays with the contributor
+        let transaction = build_contribute_only_transaction(
+            &svm,
+            &contributor2,
+            200 * 10u64.pow(6),
+            mint,
+            contributor2_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        let tx = svm
+            .send_transaction(transaction)
+            .expect("Failed to send clipped contribute tx");
+        assert!(tx
+            .logs
+            .iter()
+            .any(|log| log.contains("Contribution clipped to 90000000")));
+        assert!(tx
+            .logs
+            .iter()
+            .any(|log| log.contains("Stretch goal 1 reached")));
+
+        assert_eq!(token_balance(&svm, &vault), 750 * 10u64.pow(6));
+        assert_eq!(token_balance(&svm, &contributor2_ata), 150 * 10u64.pow(6));
+
+        let fundraiser_state = bytemuck::pod_read_unaligned::<Fundraiser>(
+            &svm.get_account(&fundraiser).unwrap().data[Fundraiser::DISCRIMINATOR.len()..],
+        );
+        assert_eq!(fundraiser_state.current_amount(), 750 * 10u64.pow(6));
+        assert_eq!(fundraiser_state.stretch_goals_reached(), 2);
+
+        svm.expire_blockhash();
+        let transaction = build_contribute_only_transaction(
+            &svm,
+            &contributor2,
+            10u64.pow(6),
+            mint,
+            contributor2_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(transaction),
+            FundraiserError::HardCapReached,
+        );
+    }
+
+    #[test]
+    pub fn test_collect_rejections() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+        let maker_ata = get_associated_token_address(&payer.pubkey(), &mint);
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams::default(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        for amount in [400_000_000, 200_000_000] {
+            let transaction = build_contribute_transaction(
+                &mut svm,
+                &payer,
+                amount,
+                mint,
+                contributor_ata,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            );
+            svm.send_transaction(transaction)
+                .expect("Failed to send contribute tx");
+
+            let transaction = build_collect_transaction(
+                &svm,
+                &payer,
+                mint,
+                maker_ata,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            );
+            let error = if amount == 400_000_000 {
+                FundraiserError::TargetNotMet
+            } else {
+                FundraiserError::CampaignNotEnded
+            };
+            assert_fundraiser_error(svm.send_transaction(transaction), error);
+            svm.expire_blockhash();
+        }
+
+        // proposals are only for milestone campaigns
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+        let (transaction, _) = build_create_proposal_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            0,
+            100_000_000,
+            SECONDS_TO_DAYS,
+            program_id,
+            system_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(transaction),
+            FundraiserError::MilestonesDisabled,
+        );
+    }
+
+    #[test]
+    pub fn test_milestone_rejections() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+        let maker_ata = get_associated_token_address(&payer.pubkey(), &mint);
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                quorum_bps: 5_000,
+                threshold_bps: 6_000,
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let transaction = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            600_000_000,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+
+        let (transaction, _) = build_create_proposal_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            0,
+            100_000_000,
+            SECONDS_TO_DAYS,
+            program_id,
+            system_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(transaction),
+            FundraiserError::CampaignNotEnded,
+        );
+
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+
+        // succeeded milestone campaigns keep the funds until a proposal is rejected
+        let transaction = build_refund_transaction(
+            &svm,
+            &payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(transaction),
+            FundraiserError::RefundsLocked,
+        );
+
+        for (amount, voting_period, error) in [
+            (0, SECONDS_TO_DAYS, FundraiserError::InvalidProposalAmount),
+            (
+                600_000_001,
+                SECONDS_TO_DAYS,
+                FundraiserError::InvalidProposalAmount,
+            ),
+            (100_000_000, 0, FundraiserError::InvalidVotingPeriod),
+        ] {
+            let (transaction, _) = build_create_proposal_transaction(
+                &svm,
+                &payer,
+                fundraiser,
+                0,
+                amount,
+                voting_period,
+                program_id,
+                system_program,
+            );
+            assert_fundraiser_error(svm.send_transaction(transaction), error);
+        }
+
+        let (transaction, proposal) = build_create_proposal_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            0,
+            100_000_000,
+            SECONDS_TO_DAYS,
+            program_id,
+            system_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send create proposal tx");
+
+        let execute = |svm: &LiteSVM| {
+            build_execute_proposal_transaction(
+                svm,
+                &payer,
+                payer.pubkey(),
+                maker_ata,
+                mint,
+                fundraiser,
+                vault,
+                proposal,
+                program_id,
+                token_program,
+            )
+        };
+
+        assert_fundraiser_error(
+            svm.send_transaction(execute(&svm)),
+            FundraiserError::VotingOpen,
+        );
+
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+
+        let transaction = build_vote_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            proposal,
+            true,
+            program_id,
+            system_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(transaction),
+            FundraiserError::VotingClosed,
+        );
+
+        // nobody voted, so quorum fails and the campaign flips to refunding
+        svm.send_transaction(execute(&svm))
+            .expect("Failed to send execute proposal tx");
+
+        svm.expire_blockhash();
+        assert_fundraiser_error(
+            svm.send_transaction(execute(&svm)),
+            FundraiserError::ProposalAlreadyExecuted,
+        );
+    }
+
+    #[test]
+    pub fn test_vesting_claim_and_flag() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+        let maker_ata = contributor_ata;
+
+        // vests over 10 days after the 1 day campaign, nothing for the first 2
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                quorum_bps: 5_000,
+                vesting_duration: 10 * SECONDS_TO_DAYS,
+                vesting_cliff: 2 * SECONDS_TO_DAYS,
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let transaction = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            600 * 10u64.pow(6),
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+
+        let claim = |svm: &LiteSVM| {
+            build_claim_transaction(
+                svm,
+                &payer,
+                maker_ata,
+                mint,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+            )
+        };
+
+        assert_fundraiser_error(
+            svm.send_transaction(claim(&svm)),
+            FundraiserError::CampaignNotEnded,
+        );
+
+        // 1 day into vesting, still before the cliff
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+        let collect = build_collect_transaction(
+            &svm,
+            &payer,
+            mint,
+            maker_ata,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(collect),
+            FundraiserError::VestingEnabled,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(claim(&svm)),
+            FundraiserError::NothingToClaim,
+        );
+
+        // halfway through vesting
+        warp(&mut svm, 4 * SECONDS_TO_DAYS);
+        svm.send_transaction(claim(&svm))
+            .expect("Failed to send claim tx");
+        assert_eq!(token_balance(&svm, &vault), 300 * 10u64.pow(6));
+
+        svm.expire_blockhash();
+        assert_fundraiser_error(
+            svm.send_transaction(claim(&svm)),
+            FundraiserError::NothingToClaim,
+        );
+
+        // the only contributor holds 100% >= the 50% quorum, so one flag stops vesting
+        let flag = build_flag_transaction(&svm, &payer, fundraiser, program_id);
+        svm.send_transaction(flag).expect("Failed to send flag tx");
+
+        svm.expire_blockhash();
+        let flag = build_flag_transaction(&svm, &payer, fundraiser, program_id);
+        assert_fundraiser_error(
+            svm.send_transaction(flag),
+            FundraiserError::CampaignRefunding,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(claim(&svm)),
+            FundraiserError::CampaignRefunding,
+        );
+
+        // the unvested half goes back to the contributor
+        let balance_before = token_balance(&svm, &contributor_ata);
+        let refund = build_refund_transaction(
+            &svm,
+            &payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(refund)
+            .expect("Failed to send refund tx");
+        assert_eq!(token_balance(&svm, &vault), 0);
+        assert_eq!(
+            token_balance(&svm, &contributor_ata),
+            balance_before + 300 * 10u64.pow(6)
+        );
+    }
+
+    #[test]
+    pub fn test_native_sol_fundraiser() {
+        let (
+            mut svm,
+            payer,
+            _mint,
+            _contributor_ata,
+            fundraiser,
+            _vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+        svm.airdrop(&payer.pubkey(), 100 * LAMPORTS_PER_SOL)
+            .expect("Airdrop failed");
+
+        // the system program stands in for the mint, the fundraiser PDA for the
+        // vault and the wallets for their token accounts
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            system_program,
+            fundraiser,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                amount_to_raise: 60 * LAMPORTS_PER_SOL,
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+        let rent_floor = svm.get_account(&fundraiser).unwrap().lamports;
+
+        let contribute = |svm: &LiteSVM, amount: u64| {
+            build_contribute_only_transaction(
+                svm,
+                &payer,
+                amount,
+                system_program,
+                payer.pubkey(),
+                fundraiser,
+                fundraiser,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            )
+        };
+
+        svm.send_transaction(contribute(&svm, 10 * LAMPORTS_PER_SOL))
+            .expect("Failed to send contribute tx");
+        assert_eq!(
+            svm.get_account(&fundraiser).unwrap().lamports,
+            rent_floor + 10 * LAMPORTS_PER_SOL
+        );
+
+        let refund = build_refund_transaction(
+            &svm,
+            &payer,
+            system_program,
+            payer.pubkey(),
+            fundraiser,
+            fundraiser,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(refund),
+            FundraiserError::CampaignNotEnded,
+        );
+
+        svm.expire_blockhash();
+        svm.send_transaction(contribute(&svm, 50 * LAMPORTS_PER_SOL))
+            .expect("Failed to send contribute tx");
+
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+        let balance_before = svm.get_account(&payer.pubkey()).unwrap().lamports;
+        let collect = build_collect_transaction(
+            &svm,
+            &payer,
+            system_program,
+            payer.pubkey(),
+            fundraiser,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(collect)
+            .expect("Failed to send collect tx");
+
+        // everything raised left, the PDA keeps its rent exemption
+        assert_eq!(svm.get_account(&fundraiser).unwrap().lamports, rent_floor);
+        assert!(
+            svm.get_account(&payer.pubkey()).unwrap().lamports
+                > balance_before + 59 * LAMPORTS_PER_SOL
+        );
+    }
+
+    #[test]
+    pub fn test_multi_mint_rejections() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+
+        let accepted_mint = CreateMint::new(&mut svm, &payer)
+            .decimals(9)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let accepted_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &accepted_mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(
+            &mut svm,
+            &payer,
+            &accepted_mint,
+            &accepted_ata,
+            10u64.pow(12),
+        )
+        .send()
+        .unwrap();
+        let accepted_vault = get_associated_token_address(&fundraiser, &accepted_mint);
+
+        // owned by switchboard, but not a pull feed
+        let feed = Pubkey::new_unique();
+        svm.set_account(
+            feed,
+            solana_account::Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: vec![0u8; 128],
+                owner: Pubkey::from(crate::constant::SWITCHBOARD_ON_DEMAND_MAINNET),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let cases = vec![
+            (
+                InitParams {
+                    accepted_mints: vec![(accepted_mint, Pubkey::new_unique())],
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidPriceFeed,
+            ),
+            (
+                InitParams {
+                    accepted_mints: vec![(mint, feed)],
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidAcceptedMints,
+            ),
+            (
+                InitParams {
+                    quorum_bps: 5_000,
+                    threshold_bps: 5_000,
+                    accepted_mints: vec![(accepted_mint, feed)],
+                    ..InitParams::default()
+                },
+                FundraiserError::InvalidAcceptedMints,
+            ),
+        ];
+
+        for (params, error) in cases {
+            let transaction = build_init_transaction(
+                &svm,
+                &payer,
+                mint,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+                &params,
+            );
+            assert_fundraiser_error(svm.send_transaction(transaction), error);
+        }
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                accepted_mints: vec![(accepted_mint, feed)],
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+        assert_eq!(token_balance(&svm, &accepted_vault), 0);
+
+        let cases = vec![
+            (2, Some(feed), FundraiserError::InvalidAcceptedMints),
+            (0, None, FundraiserError::InvalidAcceptedMints),
+            (
+                1,
+                Some(Pubkey::new_unique()),
+                FundraiserError::InvalidPriceFeed,
+            ),
+            (1, Some(feed), FundraiserError::InvalidPriceFeed),
+        ];
+
+        for (mint_index, price_feed, error) in cases {
+            let transaction = build_contribute_accepted_transaction(
+                &svm,
+                &payer,
+                10u64.pow(9),
+                mint_index,
+                price_feed,
+                accepted_mint,
+                accepted_ata,
+                fundraiser,
+                accepted_vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            );
+            assert_fundraiser_error(svm.send_transaction(transaction), error);
+        }
+
+        // mint_to_raise keeps counting 1:1
+        let transaction = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            10u64.pow(6),
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+        assert_eq!(token_balance(&svm, &vault), 10u64.pow(6));
+    }
+
+    #[test]
+    pub fn test_multi_mint_contribution() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+
+        let accepted_mint = CreateMint::new(&mut svm, &payer)
+            .decimals(9)
+            .authority(&payer.pubkey())
+            .send()
+            .unwrap();
+        let accepted_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &accepted_mint)
+            .owner(&payer.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(
+            &mut svm,
+            &payer,
+            &accepted_mint,
+            &accepted_ata,
+            10u64.pow(12),
+        )
+        .send()
+        .unwrap();
+        let accepted_vault = get_associated_token_address(&fundraiser, &accepted_mint);
+
+        // one accepted token is worth 2 tokens of mint_to_raise
+        svm.warp_to_slot(1_000);
+        let feed = Pubkey::new_unique();
+        set_pull_feed(&mut svm, &feed, 1_000, 2 * 10i128.pow(18));
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                accepted_mints: vec![(accepted_mint, feed)],
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let transaction = build_contribute_accepted_transaction(
+            &svm,
+            &payer,
+            10u64.pow(9),
+            1,
+            Some(feed),
+            accepted_mint,
+            accepted_ata,
+            fundraiser,
+            accepted_vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send accepted contribute tx");
+
+        let transaction = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            10u64.pow(6),
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+        let payer_balance = token_balance(&svm, &contributor_ata);
+
+        // the pledge is quoted in mint_to_raise, the deposits stay raw per mint
+        let (contributor_pda, _) = Pubkey::find_program_address(
+            &[b"contributor", fundraiser.as_ref(), payer.pubkey().as_ref()],
+            &program_id,
+        );
+        let contributor_state = bytemuck::pod_read_unaligned::<Contributor>(
+            &svm.get_account(&contributor_pda).unwrap().data[Contributor::DISCRIMINATOR.len()..],
+        );
+        assert_eq!(contributor_state.amount(), 3 * 10u64.pow(6));
+        assert_eq!(contributor_state.deposit(0), 10u64.pow(6));
+        assert_eq!(contributor_state.deposit(1), 10u64.pow(9));
+        let fundraiser_state = bytemuck::pod_read_unaligned::<Fundraiser>(
+            &svm.get_account(&fundraiser).unwrap().data[Fundraiser::DISCRIMINATOR.len()..],
+        );
+        assert_eq!(fundraiser_state.current_amount(), 3 * 10u64.pow(6));
+        assert_eq!(token_balance(&svm, &accepted_vault), 10u64.pow(9));
+        assert_eq!(token_balance(&svm, &vault), 10u64.pow(6));
+
+        // the campaign misses its target, each mint comes back as deposited
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+        let mut refund_ix = refund_instruction(
+            &payer.pubkey(),
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        refund_ix.accounts.extend([
+            AccountMeta::new(accepted_vault, false), // accepted vault
+            AccountMeta::new(accepted_ata, false),   // contributor's accepted token account
+        ]);
+        let message = Message::new(&[refund_ix], Some(&payer.pubkey()));
+        svm.send_transaction(Transaction::new(&[&payer], message, svm.latest_blockhash()))
+            .expect("Failed to send refund tx");
+
+        assert_eq!(token_balance(&svm, &accepted_vault), 0);
+        assert_eq!(token_balance(&svm, &accepted_ata), 10u64.pow(12));
+        assert_eq!(token_balance(&svm, &vault), 0);
+        assert_eq!(
+            token_balance(&svm, &contributor_ata),
+            payer_balance + 10u64.pow(6)
+        );
+    }
+
+    #[test]
+    pub fn test_cancel_instruction() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams::default(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let contribute = |svm: &mut LiteSVM| {
+            build_contribute_transaction(
+                svm,
+                &payer,
+                600 * 10u64.pow(6),
+                mint,
+                contributor_ata,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            )
+        };
+        let transaction = contribute(&mut svm);
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+
+        // only the maker can cancel
+        let stranger = Keypair::new();
+        svm.airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        assert_fundraiser_error(
+            svm.send_transaction(build_cancel_transaction(
+                &svm, &stranger, fundraiser, program_id,
+            )),
+            FundraiserError::InvalidPda,
+        );
+
+        svm.send_transaction(build_cancel_transaction(
+            &svm, &payer, fundraiser, program_id,
+        ))
+        .expect("Failed to send cancel tx");
+
+        svm.expire_blockhash();
+        assert_fundraiser_error(
+            svm.send_transaction(build_cancel_transaction(
+                &svm, &payer, fundraiser, program_id,
+            )),
+            FundraiserError::CampaignCancelled,
+        );
+        let transaction = contribute(&mut svm);
+        assert_fundraiser_error(
+            svm.send_transaction(transaction),
+            FundraiserError::CampaignCancelled,
+        );
+
+        // the target was met, but a cancelled campaign never collects
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+        let collect = build_collect_transaction(
+            &svm,
+            &payer,
+            mint,
+            contributor_ata,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(collect),
+            FundraiserError::CampaignCancelled,
+        );
+
+        let refund = build_refund_transaction(
+            &svm,
+            &payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(refund)
+            .expect("Failed to send refund tx");
+        assert_eq!(token_balance(&svm, &vault), 0);
+    }
+
+    #[test]
+    pub fn test_crank_refunds() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams::default(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let amount = 100 * 10u64.pow(6);
+        let transaction = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            amount,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+        let payer_balance = token_balance(&svm, &contributor_ata);
+
+        svm.send_transaction(build_approve_receipts_transaction(
+            &svm, &payer, fundraiser, amount,
+        ))
+        .expect("Failed to send approve tx");
+
+        let mut contributors = vec![(payer.pubkey(), contributor_ata)];
+        let mut keypairs = vec![];
+        for _ in 0..2 {
+            let (contributor, ata) = create_funded_contributor(&mut svm, &payer, &mint, amount);
+            let transaction = build_contribute_only_transaction(
+                &svm,
+                &contributor,
+                amount,
+                mint,
+                ata,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            );
+            svm.send_transaction(transaction)
+                .expect("Failed to send contribute tx");
+            contributors.push((contributor.pubkey(), ata));
+            keypairs.push(contributor);
+        }
+        assert_eq!(token_balance(&svm, &vault), 3 * amount);
+
+        // anyone can crank, but not while the campaign can still succeed
+        let cranker = Keypair::new();
+        svm.airdrop(&cranker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        assert_fundraiser_error(
+            svm.send_transaction(build_crank_refunds_transaction(
+                &svm,
+                &cranker,
+                fundraiser,
+                vault,
+                &contributors,
+                program_id,
+                token_program,
+            )),
+            FundraiserError::CampaignNotFailed,
+        );
+
+        // 300 of 600 raised, the campaign ends below its target
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+
+        let pdas: Vec<Pubkey> = contributors
+            .iter()
+            .map(|(wallet, _)| {
+                Pubkey::find_program_address(
+                    &[b"contributor", fundraiser.as_ref(), wallet.as_ref()],
+                    &program_id,
+                )
+                .0
+            })
+            .collect();
+
+        // a contributor who didn't let the fundraiser burn their receipts holds up the batch
+        assert_fundraiser_error(
+            svm.send_transaction(build_crank_refunds_transaction(
+                &svm,
+                &cranker,
+                fundraiser,
+                vault,
+                &contributors,
+                program_id,
+                token_program,
+            )),
+            FundraiserError::ReceiptsNotDelegated,
+        );
+        for contributor in &keypairs {
+            svm.send_transaction(build_approve_receipts_transaction(
+                &svm,
+                contributor,
+                fundraiser,
+                amount,
+            ))
+            .expect("Failed to send approve tx");
+        }
+
+        // trailing accounts that don't make a full group are rejected, not skipped
+        let mut crank_ix =
+            crank_refunds_instruction(fundraiser, vault, &contributors, program_id, token_program);
+        crank_ix.accounts.pop();
+        let message = Message::new(&[crank_ix], Some(&cranker.pubkey()));
+        let failed = svm
+            .send_transaction(Transaction::new(
+                &[&cranker],
+                message,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("a partial group should have failed");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+        );
+
+        svm.expire_blockhash();
+        let (receipt_mint, _) = receipt_mint(&fundraiser);
+        let rent = svm.get_account(&pdas[1]).unwrap().lamports;
+        let wallet_lamports = svm.get_account(&contributors[1].0).unwrap().lamports;
+
+        svm.send_transaction(build_crank_refunds_transaction(
+            &svm,
+            &cranker,
+            fundraiser,
+            vault,
+            &contributors,
+            program_id,
+            token_program,
+        ))
+        .expect("Failed to send crank refunds tx");
+
+        // every receipt went with the refund it stood for
+        let receipt_supply =
+            spl_token::state::Mint::unpack(&svm.get_account(&receipt_mint).unwrap().data)
+                .unwrap()
+                .supply;
+        assert_eq!(receipt_supply, 0);
+        assert_eq!(token_balance(&svm, &vault), 0);
+        assert_eq!(
+            token_balance(&svm, &contributor_ata),
+            payer_balance + amount
+        );
+        for (i, (_, ata)) in contributors.iter().enumerate().skip(1) {
+            assert_eq!(token_balance(&svm, ata), amount);
+            assert!(svm
+                .get_account(&pdas[i])
+                .map_or(true, |account| account.lamports == 0));
+        }
+        // the pda rent goes back to the contributor, not the cranker
+        assert_eq!(
+            svm.get_account(&contributors[1].0).unwrap().lamports,
+            wallet_lamports + rent
+        );
+
+        // cranking again skips the closed pdas
+        svm.expire_blockhash();
+        svm.send_transaction(build_crank_refunds_transaction(
+            &svm,
+            &cranker,
+            fundraiser,
+            vault,
+            &contributors,
+            program_id,
+            token_program,
+        ))
+        .expect("Failed to send second crank refunds tx");
+
+        // cranking doesn't write the status, finalize settles it
+        assert_eq!(
+            fundraiser_status(&svm, &fundraiser),
+            FundraiserStatus::Active as u8
+        );
+        svm.send_transaction(build_finalize_transaction(
+            &svm, &cranker, fundraiser, program_id,
+        ))
+        .expect("Failed to send finalize tx");
+        assert_eq!(
+            fundraiser_status(&svm, &fundraiser),
+            FundraiserStatus::Failed as u8
+        );
+    }
+
+    #[test]
+    pub fn test_finalize_status_transitions() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams::default(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+        assert_eq!(
+            fundraiser_status(&svm, &fundraiser),
+            FundraiserStatus::Active as u8
+        );
+
+        let transaction = build_contribute_transaction(
+            &mut svm,
+            &payer,
+            600 * 10u64.pow(6),
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+
+        // a running campaign can't be settled, even once the target is met
+        let stranger = Keypair::new();
+        svm.airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        assert_fundraiser_error(
+            svm.send_transaction(build_finalize_transaction(
+                &svm, &stranger, fundraiser, program_id,
+            )),
+            FundraiserError::CampaignNotEnded,
+        );
+
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+        svm.send_transaction(build_finalize_transaction(
+            &svm, &stranger, fundraiser, program_id,
+        ))
+        .expect("Failed to send finalize tx");
+        assert_eq!(
+            fundraiser_status(&svm, &fundraiser),
+            FundraiserStatus::Succeeded as u8
+        );
+
+        svm.expire_blockhash();
+        assert_fundraiser_error(
+            svm.send_transaction(build_finalize_transaction(
+                &svm, &stranger, fundraiser, program_id,
+            )),
+            FundraiserError::InvalidStatus,
+        );
+
+        // a succeeded campaign is the maker's, not the contributors'
+        let refund = build_refund_transaction(
+            &svm,
+            &payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        );
+        assert_fundraiser_error(svm.send_transaction(refund), FundraiserError::RefundsLocked);
+
+        let collect = |svm: &LiteSVM| {
+            build_collect_transaction(
+                svm,
+                &payer,
+                mint,
+                contributor_ata,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            )
+        };
+        svm.send_transaction(collect(&svm))
+            .expect("Failed to send collect tx");
+        assert_eq!(
+            fundraiser_status(&svm, &fundraiser),
+            FundraiserStatus::Collected as u8
+        );
+
+        svm.expire_blockhash();
+        assert_fundraiser_error(
+            svm.send_transaction(collect(&svm)),
+            FundraiserError::InvalidStatus,
+        );
+    }
+
+    #[test]
+    pub fn test_allowlist() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            _contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+        let amount = 100 * 10u64.pow(6);
+
+        let (alice, alice_ata) = create_funded_contributor(&mut svm, &payer, &mint, amount);
+        let (bob, bob_ata) = create_funded_contributor(&mut svm, &payer, &mint, amount);
+        let (carol, carol_ata) = create_funded_contributor(&mut svm, &payer, &mint, amount);
+        let (mallory, mallory_ata) = create_funded_contributor(&mut svm, &payer, &mint, amount);
+
+        // root over [alice, bob, carol], carol's leaf is carried up a level
+        let (alice_leaf, bob_leaf, carol_leaf) = (
+            allowlist_leaf(&alice.pubkey()),
+            allowlist_leaf(&bob.pubkey()),
+            allowlist_leaf(&carol.pubkey()),
+        );
+        let alice_bob = hash_pair(alice_leaf, bob_leaf);
+        let root = hash_pair(alice_bob, carol_leaf);
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                allowlist_root: root,
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let contribute = |svm: &LiteSVM, contributor: &Keypair, ata: Pubkey, proof: &[[u8; 32]]| {
+            build_contribute_allowlisted_transaction(
+                svm,
+                contributor,
+                amount,
+                proof,
+                None,
+                mint,
+                ata,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            )
+        };
+
+        svm.send_transaction(contribute(&svm, &alice, alice_ata, &[bob_leaf, carol_leaf]))
+            .expect("Failed to send alice contribute tx");
+        svm.send_transaction(contribute(&svm, &carol, carol_ata, &[alice_bob]))
+            .expect("Failed to send carol contribute tx");
+
+        // a wallet off the list, replaying someone else's proof or none at all
+        assert_fundraiser_error(
+            svm.send_transaction(contribute(
+                &svm,
+                &mallory,
+                mallory_ata,
+                &[bob_leaf, carol_leaf],
+            )),
+            FundraiserError::NotAllowlisted,
+        );
+        assert_fundraiser_error(
+            svm.send_transaction(contribute(&svm, &bob, bob_ata, &[])),
+            FundraiserError::NotAllowlisted,
+        );
+        assert_eq!(token_balance(&svm, &vault), 2 * amount);
+
+        // a second campaign gated by an authority co-signing each contribution
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        let authority = Keypair::new();
+        let (fundraiser, _) = Pubkey::find_program_address(
+            &[b"fundraiser".as_ref(), maker.pubkey().as_ref()],
+            &program_id,
+        );
+        let vault = get_associated_token_address(&fundraiser, &mint);
+
+        let transaction = build_init_transaction(
+            &svm,
+            &maker,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                allowlist_authority: authority.pubkey(),
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let contribute = |svm: &LiteSVM, contributor: &Keypair, ata: Pubkey, signer: &Keypair| {
+            build_contribute_allowlisted_transaction(
+                svm,
+                contributor,
+                amount,
+                &[],
+                Some(signer),
+                mint,
+                ata,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            )
+        };
+
+        assert_fundraiser_error(
+            svm.send_transaction(contribute(&svm, &mallory, mallory_ata, &payer)),
+            FundraiserError::NotAllowlisted,
+        );
+        svm.send_transaction(contribute(&svm, &mallory, mallory_ata, &authority))
+            .expect("Failed to send co-signed contribute tx");
+        assert_eq!(token_balance(&svm, &vault), amount);
+    }
+
+    #[test]
+    pub fn test_referral_rewards() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            maker_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                referral_fee_bps: 500, // 5%
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        // referrers are plain wallets with an ATA for the raised mint
+        let (alice, alice_ata) = create_funded_contributor(&mut svm, &payer, &mint, 0);
+        let (bob, bob_ata) = create_funded_contributor(&mut svm, &payer, &mint, 0);
+
+        let amount = 200 * 10u64.pow(6);
+        let mut contributors = vec![];
+        for _ in 0..3 {
+            contributors.push(create_funded_contributor(&mut svm, &payer, &mint, amount));
+        }
+
+        let referred = |svm: &LiteSVM, contributor: &(Keypair, Pubkey), referrer: Pubkey| {
+            build_contribute_referred_transaction(
+                svm,
+                &contributor.0,
+                amount,
+                referrer,
+                mint,
+                contributor.1,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            )
+        };
+
+        // nobody refers themselves
+        assert_fundraiser_error(
+            svm.send_transaction(referred(&svm, &contributors[0], contributors[0].0.pubkey())),
+            FundraiserError::InvalidReferrer,
+        );
+
+        // alice refers two contributors, bob one
+        svm.send_transaction(referred(&svm, &contributors[0], alice.pubkey()))
+            .expect("Failed to send referred contribute tx");
+        svm.send_transaction(referred(&svm, &contributors[1], alice.pubkey()))
+            .expect("Failed to send referred contribute tx");
+        svm.send_transaction(referred(&svm, &contributors[2], bob.pubkey()))
+            .expect("Failed to send referred contribute tx");
+
+        let (alice_pda, _) = referrer_pda(&fundraiser, &alice.pubkey());
+        let (bob_pda, _) = referrer_pda(&fundraiser, &bob.pubkey());
+        let alice_state = svm.get_account(&alice_pda).unwrap();
+        assert_eq!(alice_state.data[40..48], (2 * amount).to_le_bytes());
+
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+        let maker_balance = token_balance(&svm, &maker_ata);
+
+        // every referrer has to be paid at collect
+        let collect = |svm: &LiteSVM, remaining: Vec<AccountMeta>| {
+            build_collect_with_accounts_transaction(
+                svm,
+                &payer,
+                mint,
+                maker_ata,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+                remaining,
+            )
+        };
+        assert!(svm
+            .send_transaction(collect(
+                &svm,
+                vec![
+                    AccountMeta::new(alice_pda, false),
+                    AccountMeta::new(alice_ata, false),
+                ],
+            ))
+            .is_err());
+        assert_fundraiser_error(
+            svm.send_transaction(collect(
+                &svm,
+                vec![
+                    AccountMeta::new(alice_pda, false),
+                    AccountMeta::new(bob_ata, false),
+                    AccountMeta::new(bob_pda, false),
+                    AccountMeta::new(bob_ata, false),
+                ],
+            )),
+            FundraiserError::TokenAccountMismatch,
+        );
+
+        svm.send_transaction(collect(
+            &svm,
+            vec![
+                AccountMeta::new(alice_pda, false),
+                AccountMeta::new(alice_ata, false),
+                AccountMeta::new(bob_pda, false),
+                AccountMeta::new(bob_ata, false),
+            ],
+        ))
+        .expect("Failed to send collect tx");
+
+        assert_eq!(token_balance(&svm, &alice_ata), 20 * 10u64.pow(6));
+        assert_eq!(token_balance(&svm, &bob_ata), 10 * 10u64.pow(6));
+        assert_eq!(
+            token_balance(&svm, &maker_ata),
+            maker_balance + 570 * 10u64.pow(6)
+        );
+        assert_eq!(token_balance(&svm, &vault), 0);
+    }
+
+    #[test]
+    pub fn test_account_discriminators() {
+        let (
+            mut svm,
+            payer,
+            mint,
+            contributor_ata,
+            fundraiser,
+            vault,
+            associated_token_program,
+            token_program,
+            system_program,
+        ) = setup();
+
+        let program_id = program_id();
+        let maker_ata = get_associated_token_address(&payer.pubkey(), &mint);
+
+        let transaction = build_init_transaction(
+            &svm,
+            &payer,
+            mint,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+            &InitParams {
+                quorum_bps: 5_000,
+                threshold_bps: 6_000,
+                ..InitParams::default()
+            },
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send init tx");
+
+        let contribute = |svm: &mut LiteSVM| {
+            build_contribute_transaction(
+                svm,
+                &payer,
+                600 * 10u64.pow(6),
+                mint,
+                contributor_ata,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            )
+        };
+        let refund = |svm: &LiteSVM| {
+            build_refund_transaction(
+                svm,
+                &payer,
+                mint,
+                contributor_ata,
+                fundraiser,
+                vault,
+                program_id,
+                token_program,
+                system_program,
+                associated_token_program,
+            )
+        };
+
+        let transaction = contribute(&mut svm);
+        svm.send_transaction(transaction)
+            .expect("Failed to send contribute tx");
+
+        let (contributor_pda, _) = Pubkey::find_program_address(
+            &[b"contributor", fundraiser.as_ref(), payer.pubkey().as_ref()],
+            &program_id,
+        );
+
+        // every instruction taking the fundraiser rejects another account type in its place
+        retag(&mut svm, &fundraiser, Contributor::DISCRIMINATOR);
+
+        let transaction = contribute(&mut svm);
+        assert_invalid_account_data(svm.send_transaction(transaction));
+        assert_invalid_account_data(svm.send_transaction(refund(&svm)));
+        assert_invalid_account_data(svm.send_transaction(build_collect_transaction(
+            &svm,
+            &payer,
+            mint,
+            maker_ata,
+            vault,
+            program_id,
+            token_program,
+            system_program,
+            associated_token_program,
+        )));
+        assert_invalid_account_data(
+            svm.send_transaction(
+                build_create_proposal_transaction(
+                    &svm,
+                    &payer,
+                    fundraiser,
+                    0,
+                    300_000_000,
+                    SECONDS_TO_DAYS,
+                    program_id,
+                    system_program,
+                )
+                .0,
+            ),
+        );
+        assert_invalid_account_data(svm.send_transaction(build_claim_transaction(
+            &svm,
+            &payer,
+            maker_ata,
+            mint,
+            fundraiser,
+            vault,
+            program_id,
+            token_program,
+        )));
+        assert_invalid_account_data(
+            svm.send_transaction(build_flag_transaction(&svm, &payer, fundraiser, program_id)),
+        );
+        assert_invalid_account_data(svm.send_transaction(build_cancel_transaction(
+            &svm, &payer, fundraiser, program_id,
+        )));
+        assert_invalid_account_data(svm.send_transaction(build_finalize_transaction(
+            &svm, &payer, fundraiser, program_id,
+        )));
+        assert_invalid_account_data(svm.send_transaction(build_crank_refunds_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            vault,
+            &[(contributor_pda, contributor_ata)],
+            program_id,
+            token_program,
+        )));
+
+        retag(&mut svm, &fundraiser, Fundraiser::DISCRIMINATOR);
+
+        // and the contributor PDA is checked the same way
+        retag(&mut svm, &contributor_pda, Referrer::DISCRIMINATOR);
+
+        let transaction = contribute(&mut svm);
+        assert_invalid_account_data(svm.send_transaction(transaction));
+        assert_invalid_account_data(svm.send_transaction(refund(&svm)));
+
+        retag(&mut svm, &contributor_pda, Contributor::DISCRIMINATOR);
+
+        warp(&mut svm, 2 * SECONDS_TO_DAYS);
+
+        let (transaction, proposal) = build_create_proposal_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            0,
+            300_000_000,
+            SECONDS_TO_DAYS,
+            program_id,
+            system_program,
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send create proposal tx");
+
+        // a proposal tagged as a vote receipt can be neither voted on nor executed
+        retag(&mut svm, &proposal, Vote::DISCRIMINATOR);
+
+        assert_invalid_account_data(svm.send_transaction(build_vote_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            proposal,
+            true,
+            program_id,
+            system_program,
+        )));
+        assert_invalid_account_data(svm.send_transaction(build_execute_proposal_transaction(
+            &svm,
+            &payer,
+            payer.pubkey(),
+            maker_ata,
+            mint,
+            fundraiser,
+            vault,
+            proposal,
+            program_id,
+            token_program,
+        )));
+
+        // restoring the tag restores the account
+        retag(&mut svm, &proposal, Proposal::DISCRIMINATOR);
+
+        svm.send_transaction(build_vote_transaction(
+            &svm,
+            &payer,
+            fundraiser,
+            proposal,
+            true,
+            program_id,
+            system_program,
+        ))
+        .expect("Failed to send vote tx");
+    }
 }
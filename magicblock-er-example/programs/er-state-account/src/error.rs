@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErStateError {
+    #[msg("Counter names and map keys can't be all zeroes")]
+    InvalidKey,
+    #[msg("No counter or entry with this key")]
+    KeyNotFound,
+    #[msg("Every counter slot is already named")]
+    CountersFull,
+    #[msg("The map is full, resize the account to add entries")]
+    MapFull,
+    #[msg("Capacity is above the maximum or below the current number of entries")]
+    InvalidCapacity,
+    #[msg("Value overflowed")]
+    Overflow,
+    #[msg("Value underflowed")]
+    Underflow,
+    #[msg("The commit policy is due, pass the magic context and magic program to commit")]
+    CommitRequired,
+    #[msg("Account doesn't match the seeds it was passed with")]
+    InvalidPda,
+    #[msg("Remaining accounts don't match the PDAs passed")]
+    AccountCountMismatch,
+    #[msg("Every inventory slot is already taken")]
+    InventoryFull,
+    #[msg("A session holds 1 to 4 players")]
+    InvalidMaxPlayers,
+    #[msg("The session no longer accepts players")]
+    SessionNotOpen,
+    #[msg("The session isn't running on the ephemeral rollup")]
+    SessionNotActive,
+    #[msg("The session is full")]
+    SessionFull,
+    #[msg("Already joined this session")]
+    AlreadyJoined,
+    #[msg("Signer isn't a player of this session")]
+    NotAPlayer,
+    #[msg("Stake has to be above zero")]
+    InvalidStake,
+    #[msg("Guess a die face, 1 to 6")]
+    InvalidGuess,
+    #[msg("The previous wager isn't resolved yet")]
+    WagerInProgress,
+    #[msg("Wager is not open for a roll")]
+    WagerNotOpen,
+    #[msg("Wager has no roll pending")]
+    WagerNotPending,
+    #[msg("The roll can only be refunded after the timeout")]
+    WagerNotExpired,
+    #[msg("The house can't cover this payout")]
+    HouseUnderfunded,
+    #[msg("A randomness request is already outstanding")]
+    RequestPending,
+    #[msg("No randomness request is outstanding")]
+    NoPendingRequest,
+    #[msg("Callback doesn't answer the outstanding request")]
+    RequestMismatch,
+    #[msg("Random table is too long or has an invalid row")]
+    InvalidRandomTable,
+    #[msg("Randomness request can't be drawn from the current tables")]
+    InvalidRandomRequest,
+    #[msg("Undelegate the account before closing it")]
+    AccountDelegated,
+    #[msg("Balance too low for this transfer or withdrawal")]
+    InsufficientBalance,
+    #[msg("Transfers need a positive amount and another balance")]
+    InvalidTransfer,
+    #[msg("The vault holds less than the balances it backs")]
+    VaultShortfall,
+    #[msg("A move must score between 1 and MAX_POINTS_PER_MOVE points")]
+    InvalidPoints,
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
-use crate::state::UserAccount;
+use crate::error::ErStateError;
+use crate::state::{PendingRandomness, UserAccount};
 
 #[derive(Accounts)]
 pub struct CloseUser<'info> {
@@ -11,8 +12,17 @@ pub struct CloseUser<'info> {
         close = user,
         seeds = [b"user", user.key().as_ref()],
         bump = user_account.bump,
+        // in the ER the program still owns the account, closing it there would strand the delegation
+        constraint = !user_account.delegation.delegated @ ErStateError::AccountDelegated,
     )]
     pub user_account: Box<Account<'info, UserAccount>>,
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"randomness", user.key().as_ref()],
+        bump = pending_randomness.bump,
+    )]
+    pub pending_randomness: Box<Account<'info, PendingRandomness>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{LootEntry, UserAccount};
+
+/// Sets the tables VRF callbacks draw from, on the base layer or in the ER.
+#[derive(Accounts)]
+pub struct ConfigureRandom<'info> {
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+impl<'info> ConfigureRandom<'info> {
+    pub fn set_weights(&mut self, weights: &[u32]) -> Result<()> {
+        self.user_account.random.set_weights(weights)
+    }
+
+    pub fn set_list(&mut self, list: &[u8]) -> Result<()> {
+        self.user_account.random.set_list(list)
+    }
+
+    pub fn set_loot_table(&mut self, loot: &[LootEntry]) -> Result<()> {
+        self.user_account.random.set_loot(loot)
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErStateError;
+use crate::state::{GameSession, PlayerState, SessionStatus, MAX_PLAYERS};
+
+#[derive(Accounts)]
+#[instruction(session_id: u64)]
+pub struct CreateSession<'info> {
+    #[account(mut)]
+    pub host: Signer<'info>,
+    #[account(
+        init,
+        payer = host,
+        space = 8 + GameSession::INIT_SPACE,
+        seeds = [b"session", host.key().as_ref(), &session_id.to_le_bytes()],
+        bump
+    )]
+    pub session: Account<'info, GameSession>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateSession<'info> {
+    pub fn create_session(
+        &mut self,
+        session_id: u64,
+        max_players: u8,
+        bumps: &CreateSessionBumps,
+    ) -> Result<()> {
+        require!(
+            (1..=MAX_PLAYERS as u8).contains(&max_players),
+            ErStateError::InvalidMaxPlayers
+        );
+
+        let mut players = [PlayerState::default(); MAX_PLAYERS];
+        players[0].player = self.host.key();
+
+        self.session.set_inner(GameSession {
+            host: self.host.key(),
+            session_id,
+            bump: bumps.session,
+            status: SessionStatus::Open,
+            max_players,
+            player_count: 1,
+            players,
+            move_count: 0,
+            last_move_slot: 0,
+        });
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use ephemeral_rollups_sdk::{anchor::delegate, cpi::DelegateConfig};
 
-use crate::state::UserAccount;
+use crate::state::{CommitPolicy, PendingRandomness, UserAccount};
 
 #[delegate]
 #[derive(Accounts)]
@@ -15,6 +15,14 @@ pub struct Delegate<'info> {
         bump = user_account.bump,
     )]
     pub user_account: Account<'info, UserAccount>,
+    // goes along so VRF requests can be made on the ER
+    #[account(
+        mut,
+        del,
+        seeds = [b"randomness", user.key().as_ref()],
+        bump = pending_randomness.bump,
+    )]
+    pub pending_randomness: Account<'info, PendingRandomness>,
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub validator: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
@@ -22,7 +30,13 @@ pub struct Delegate<'info> {
 
 impl<'info> Delegate<'info> {
     
-    pub fn delegate(&mut self) -> Result<()> {
+    pub fn delegate(&mut self, policy: CommitPolicy) -> Result<()> {
+
+        // the policy and delegation record travel with the delegated data,
+        // so they are written out before the CPI
+        self.user_account.commit_policy = policy;
+        self.user_account.start_delegation(self.validator.key(), Clock::get()?.slot);
+        self.user_account.exit(&crate::ID)?;
 
         let pda_seeds: &[&[u8]] = &[
             b"user",
@@ -39,6 +53,17 @@ impl<'info> Delegate<'info> {
             }
         )?;
 
+        let randomness_seeds: &[&[u8]] = &[b"randomness", self.user.key.as_ref()];
+
+        self.delegate_pending_randomness(
+            &self.user,
+            randomness_seeds,
+            DelegateConfig {
+                validator: Some(self.validator.key()),
+                ..DelegateConfig::default()
+            },
+        )?;
+
         Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::{anchor::delegate, cpi::DelegateConfig};
+
+use crate::state::Balance;
+
+#[delegate]
+#[derive(Accounts)]
+pub struct DelegateBalance<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        del,
+        seeds = [b"balance", balance.mint.as_ref(), user.key().as_ref()],
+        bump = balance.bump,
+    )]
+    pub balance: Account<'info, Balance>,
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub validator: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DelegateBalance<'info> {
+    /// Moves the balance to the ER validator, where `transfer_balance` runs.
+    pub fn delegate(&mut self) -> Result<()> {
+        let mint = self.balance.mint;
+        let pda_seeds: &[&[u8]] = &[b"balance", mint.as_ref(), self.user.key.as_ref()];
+
+        self.delegate_balance(
+            &self.user,
+            pda_seeds,
+            DelegateConfig {
+                validator: Some(self.validator.key()),
+                ..DelegateConfig::default()
+            },
+        )?;
+
+        Ok(())
+    }
+}
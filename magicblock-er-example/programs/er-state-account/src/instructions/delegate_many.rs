@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::{
+    consts::DELEGATION_PROGRAM_ID,
+    cpi::{delegate_account, DelegateAccounts, DelegateConfig},
+};
+
+use crate::error::ErStateError;
+use crate::state::{DelegatedPda, UserAccount};
+
+#[derive(Accounts)]
+pub struct DelegateMany<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub validator: UncheckedAccount<'info>,
+    /// CHECK: This program, owner of the PDAs being delegated
+    #[account(address = crate::ID)]
+    pub owner_program: UncheckedAccount<'info>,
+    /// CHECK: The delegation program
+    #[account(address = DELEGATION_PROGRAM_ID)]
+    pub delegation_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DelegateMany<'info> {
+    /// Delegates every PDA in `pdas`, taking
+    /// `[pda, buffer, delegation_record, delegation_metadata]` remaining accounts for each.
+    pub fn delegate_many(
+        &mut self,
+        pdas: &[DelegatedPda],
+        remaining: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(
+            remaining.len() == pdas.len() * 4,
+            ErStateError::AccountCountMismatch
+        );
+
+        let user_info = self.user.to_account_info();
+        let owner_program = self.owner_program.to_account_info();
+        let delegation_program = self.delegation_program.to_account_info();
+        let system_program = self.system_program.to_account_info();
+        let slot = Clock::get()?.slot;
+
+        for (pda, accounts) in pdas.iter().zip(remaining.chunks_exact(4)) {
+            // every seed set is checked before anything is handed to the delegation program
+            let seeds = pda.verify(self.user.key, &accounts[0])?;
+            let seed_slices: Vec<&[u8]> = seeds.iter().map(|s| s.as_slice()).collect();
+
+            if let DelegatedPda::User = pda {
+                let mut user_account = Account::<UserAccount>::try_from(&accounts[0])?;
+                user_account.start_delegation(self.validator.key(), slot);
+                user_account.exit(&crate::ID)?;
+            }
+
+            delegate_account(
+                DelegateAccounts {
+                    payer: &user_info,
+                    pda: &accounts[0],
+                    owner_program: &owner_program,
+                    buffer: &accounts[1],
+                    delegation_record: &accounts[2],
+                    delegation_metadata: &accounts[3],
+                    delegation_program: &delegation_program,
+                    system_program: &system_program,
+                },
+                &seed_slices,
+                DelegateConfig {
+                    validator: Some(self.validator.key()),
+                    ..DelegateConfig::default()
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::{anchor::delegate, cpi::DelegateConfig};
+
+use crate::error::ErStateError;
+use crate::state::{GameSession, SessionStatus};
+
+#[delegate]
+#[derive(Accounts)]
+pub struct DelegateSession<'info> {
+    #[account(mut)]
+    pub host: Signer<'info>,
+    #[account(
+        mut,
+        del,
+        has_one = host,
+        seeds = [b"session", host.key().as_ref(), &session.session_id.to_le_bytes()],
+        bump = session.bump,
+    )]
+    pub session: Account<'info, GameSession>,
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    pub validator: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DelegateSession<'info> {
+    pub fn delegate(&mut self) -> Result<()> {
+        require!(
+            self.session.status == SessionStatus::Open,
+            ErStateError::SessionNotOpen
+        );
+
+        // no one joins once the session runs on the ER
+        self.session.status = SessionStatus::Active;
+        self.session.exit(&crate::ID)?;
+
+        let session_id = self.session.session_id.to_le_bytes();
+        let pda_seeds: &[&[u8]] = &[b"session", self.host.key.as_ref(), &session_id];
+
+        self.delegate_session(
+            &self.host,
+            pda_seeds,
+            DelegateConfig {
+                validator: Some(self.validator.key()),
+                ..DelegateConfig::default()
+            },
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::error::ErStateError;
+use crate::state::{Balance, TokenVault};
+
+#[derive(Accounts)]
+pub struct DepositTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + TokenVault::INIT_SPACE,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenVault>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+    // a delegated balance is owned by the delegation program, so this fails until it's settled
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + Balance::INIT_SPACE,
+        seeds = [b"balance", mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub balance: Account<'info, Balance>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositTokens<'info> {
+    /// Moves `amount` into the vault and credits what arrived to the user's balance.
+    /// A zero deposit only opens the balance, so it can receive transfers.
+    pub fn deposit(&mut self, amount: u64, bumps: &DepositTokensBumps) -> Result<()> {
+        self.vault.mint = self.mint.key();
+        self.vault.bump = bumps.vault;
+        self.balance.owner = self.user.key();
+        self.balance.mint = self.mint.key();
+        self.balance.bump = bumps.balance;
+
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let before = self.vault_ata.amount;
+        transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.user_ata.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to: self.vault_ata.to_account_info(),
+                    authority: self.user.to_account_info(),
+                },
+            ),
+            amount,
+            self.mint.decimals,
+        )?;
+
+        // transfer fee mints deliver less than `amount`
+        self.vault_ata.reload()?;
+        let received = self
+            .vault_ata
+            .amount
+            .checked_sub(before)
+            .ok_or(ErStateError::Underflow)?;
+
+        self.balance.amount = self
+            .balance
+            .amount
+            .checked_add(received)
+            .ok_or(ErStateError::Overflow)?;
+        self.vault.total_balances = self
+            .vault
+            .total_balances
+            .checked_add(received)
+            .ok_or(ErStateError::Overflow)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::state::House;
+
+#[derive(Accounts)]
+pub struct FundHouse<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + House::INIT_SPACE,
+        seeds = [b"house"],
+        bump
+    )]
+    pub house: Account<'info, House>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FundHouse<'info> {
+    pub fn fund_house(&mut self, amount: u64, bumps: &FundHouseBumps) -> Result<()> {
+        self.house.bump = bumps.house;
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.funder.to_account_info(),
+                    to: self.house.to_account_info(),
+                },
+            ),
+            amount,
+        )
+    }
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Counter, Inventory, MAX_ITEMS};
+
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct InitInventory<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Inventory::INIT_SPACE,
+        seeds = [b"inventory", user.key().as_ref(), &[index]],
+        bump
+    )]
+    pub inventory: Account<'info, Inventory>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitInventory<'info> {
+    pub fn init_inventory(&mut self, index: u8, bumps: &InitInventoryBumps) -> Result<()> {
+        self.inventory.set_inner(Inventory {
+            user: self.user.key(),
+            index,
+            bump: bumps.inventory,
+            items: [Counter::default(); MAX_ITEMS],
+        });
+
+        Ok(())
+    }
+}
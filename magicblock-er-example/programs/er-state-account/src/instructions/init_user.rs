@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
 
-use crate::state::UserAccount;
+use crate::state::{
+    CommitPolicy, Counter, DelegationInfo, PendingRandomness, RandomRequest, RandomState, UserAccount,
+    DEFAULT_CAPACITY, MAX_COUNTERS,
+};
 
 #[derive(Accounts)]
 pub struct InitUser<'info> {
@@ -9,11 +12,19 @@ pub struct InitUser<'info> {
     #[account(
         init,
         payer = user,
-        space = UserAccount::INIT_SPACE,
+        space = UserAccount::space(DEFAULT_CAPACITY),
         seeds = [b"user", user.key().as_ref()],
         bump
     )]
     pub user_account: Account<'info, UserAccount>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + PendingRandomness::INIT_SPACE,
+        seeds = [b"randomness", user.key().as_ref()],
+        bump
+    )]
+    pub pending_randomness: Account<'info, PendingRandomness>,
     pub system_program: Program<'info, System>,
 }
 
@@ -22,7 +33,26 @@ impl<'info> InitUser<'info> {
         self.user_account.set_inner(UserAccount { 
             user: *self.user.key, 
             data: 0, 
-            bump: bumps.user_account 
+            bump: bumps.user_account,
+            last_updated_slot: Clock::get()?.slot,
+            commit_policy: CommitPolicy::default(),
+            updates_since_commit: 0,
+            last_commit_slot: 0,
+            delegation: DelegationInfo::default(),
+            counters: [Counter::default(); MAX_COUNTERS],
+            random: RandomState::default(),
+            capacity: DEFAULT_CAPACITY,
+            entries: Vec::new(),
+        });
+
+        self.pending_randomness.set_inner(PendingRandomness {
+            user: *self.user.key,
+            bump: bumps.pending_randomness,
+            pending: false,
+            seed: [0; 32],
+            slot: 0,
+            nonce: 0,
+            request: RandomRequest::Dice,
         });
         
         Ok(())
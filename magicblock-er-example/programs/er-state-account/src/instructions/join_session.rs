@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErStateError;
+use crate::state::{GameSession, PlayerState, SessionStatus};
+
+#[derive(Accounts)]
+pub struct JoinSession<'info> {
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"session", session.host.as_ref(), &session.session_id.to_le_bytes()],
+        bump = session.bump,
+    )]
+    pub session: Account<'info, GameSession>,
+}
+
+impl<'info> JoinSession<'info> {
+    pub fn join_session(&mut self) -> Result<()> {
+        let player = self.player.key();
+        let session = &mut self.session;
+
+        require!(
+            session.status == SessionStatus::Open,
+            ErStateError::SessionNotOpen
+        );
+        require!(
+            session.player_mut(&player).is_none(),
+            ErStateError::AlreadyJoined
+        );
+        require!(
+            session.player_count < session.max_players,
+            ErStateError::SessionFull
+        );
+
+        let index = session.player_count as usize;
+        session.players[index] = PlayerState {
+            player,
+            score: 0,
+            moves: 0,
+        };
+        session.player_count += 1;
+
+        Ok(())
+    }
+}
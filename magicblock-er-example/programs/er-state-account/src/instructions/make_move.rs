@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErStateError;
+use crate::state::{GameSession, SessionStatus, MAX_POINTS_PER_MOVE};
+
+#[derive(Accounts)]
+pub struct MakeMove<'info> {
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"session", session.host.as_ref(), &session.session_id.to_le_bytes()],
+        bump = session.bump,
+    )]
+    pub session: Account<'info, GameSession>,
+}
+
+impl<'info> MakeMove<'info> {
+    /// A player-signed move on the ER, scoring `points` (1..=`MAX_POINTS_PER_MOVE`).
+    pub fn make_move(&mut self, points: u64) -> Result<()> {
+        require!(
+            self.session.status == SessionStatus::Active,
+            ErStateError::SessionNotActive
+        );
+        require!(
+            (1..=MAX_POINTS_PER_MOVE).contains(&points),
+            ErStateError::InvalidPoints
+        );
+
+        let player = self
+            .session
+            .player_mut(self.player.key)
+            .ok_or(ErStateError::NotAPlayer)?;
+        player.score = player
+            .score
+            .checked_add(points)
+            .ok_or(ErStateError::Overflow)?;
+        player.moves = player.moves.saturating_add(1);
+
+        self.session.move_count = self.session.move_count.saturating_add(1);
+        self.session.last_move_slot = Clock::get()?.slot;
+
+        Ok(())
+    }
+}
@@ -4,6 +4,9 @@ pub use init_user::*;
 pub mod update_user;
 pub use update_user::*;
 
+pub mod resize_user;
+pub use resize_user::*;
+
 pub mod update_commit;
 pub use update_commit::*;
 
@@ -13,8 +16,65 @@ pub use delegate::*;
 pub mod undelegate;
 pub use undelegate::*;
 
+pub mod init_inventory;
+pub use init_inventory::*;
+
+pub mod update_inventory;
+pub use update_inventory::*;
+
+pub mod delegate_many;
+pub use delegate_many::*;
+
+pub mod undelegate_many;
+pub use undelegate_many::*;
+
+pub mod create_session;
+pub use create_session::*;
+
+pub mod join_session;
+pub use join_session::*;
+
+pub mod delegate_session;
+pub use delegate_session::*;
+
+pub mod make_move;
+pub use make_move::*;
+
+pub mod settle_session;
+pub use settle_session::*;
+
+pub mod fund_house;
+pub use fund_house::*;
+
+pub mod place_wager;
+pub use place_wager::*;
+
+pub mod roll_wager;
+pub use roll_wager::*;
+
+pub mod refund_wager;
+pub use refund_wager::*;
+
 pub mod close_user;
 pub use close_user::*;
 
+pub mod deposit_tokens;
+pub use deposit_tokens::*;
+
+pub mod delegate_balance;
+pub use delegate_balance::*;
+
+pub mod transfer_balance;
+pub use transfer_balance::*;
+
+pub mod settle_balance;
+pub use settle_balance::*;
+
+pub mod withdraw_tokens;
+pub use withdraw_tokens::*;
+
+pub mod configure_random;
+pub use configure_random::*;
+
 pub mod update_user_with_random;
 pub use update_user_with_random::*;
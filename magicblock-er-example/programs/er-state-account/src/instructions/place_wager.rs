@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::error::ErStateError;
+use crate::state::{Wager, WagerStatus};
+
+#[derive(Accounts)]
+pub struct PlaceWager<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + Wager::INIT_SPACE,
+        seeds = [b"wager", user.key().as_ref()],
+        bump
+    )]
+    pub wager: Account<'info, Wager>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceWager<'info> {
+    pub fn place_wager(&mut self, stake: u64, guess: u8, bumps: &PlaceWagerBumps) -> Result<()> {
+        require!(stake > 0, ErStateError::InvalidStake);
+        require!((1..=6).contains(&guess), ErStateError::InvalidGuess);
+        // one wager at a time, the previous one has to be resolved or refunded
+        require!(self.wager.settled(), ErStateError::WagerInProgress);
+
+        self.wager.set_inner(Wager {
+            user: self.user.key(),
+            bump: bumps.wager,
+            status: WagerStatus::Open,
+            stake,
+            guess,
+            request_id: [0; 32],
+            requested_slot: 0,
+            roll: 0,
+        });
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.user.to_account_info(),
+                    to: self.wager.to_account_info(),
+                },
+            ),
+            stake,
+        )
+    }
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErStateError;
+use crate::state::{move_lamports, House, Wager, WagerStatus, WAGER_TIMEOUT_SLOTS};
+
+#[derive(Accounts)]
+pub struct RefundWager<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        has_one = user,
+        seeds = [b"wager", user.key().as_ref()],
+        bump = wager.bump,
+    )]
+    pub wager: Account<'info, Wager>,
+    #[account(
+        mut,
+        seeds = [b"house"],
+        bump = house.bump,
+    )]
+    pub house: Account<'info, House>,
+}
+
+impl<'info> RefundWager<'info> {
+    /// Returns the stake of a wager never rolled, or whose callback never arrived.
+    pub fn refund_wager(&mut self) -> Result<()> {
+        let wager = self.wager.to_account_info();
+
+        match self.wager.status {
+            WagerStatus::Open => {}
+            WagerStatus::Pending => {
+                let expires_at = self
+                    .wager
+                    .requested_slot
+                    .checked_add(WAGER_TIMEOUT_SLOTS)
+                    .ok_or(ErStateError::Overflow)?;
+                require!(
+                    Clock::get()?.slot >= expires_at,
+                    ErStateError::WagerNotExpired
+                );
+                // the house gets back its locked share
+                let house_share = self.wager.house_share().ok_or(ErStateError::Overflow)?;
+                move_lamports(&wager, &self.house.to_account_info(), house_share)?;
+            }
+            _ => return err!(ErStateError::WagerNotPending),
+        }
+
+        move_lamports(&wager, &self.user.to_account_info(), self.wager.stake)?;
+        self.wager.status = WagerStatus::Refunded;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErStateError;
+use crate::state::{UserAccount, MAX_CAPACITY};
+
+#[derive(Accounts)]
+#[instruction(capacity: u16)]
+pub struct ResizeUser<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        realloc = UserAccount::space(capacity),
+        realloc::payer = user,
+        realloc::zero = false,
+        seeds = [b"user", user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ResizeUser<'info> {
+    pub fn resize(&mut self, capacity: u16) -> Result<()> {
+        // shrinking refunds the rent, but never below the entries already stored
+        require!(
+            capacity <= MAX_CAPACITY && capacity as usize >= self.user_account.entries.len(),
+            ErStateError::InvalidCapacity
+        );
+
+        self.user_account.capacity = capacity;
+        self.user_account.last_updated_slot = Clock::get()?.slot;
+
+        Ok(())
+    }
+}
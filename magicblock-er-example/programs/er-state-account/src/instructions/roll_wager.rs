@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use ephemeral_vrf_sdk::anchor::vrf;
+use ephemeral_vrf_sdk::instructions::{create_request_randomness_ix, RequestRandomnessParams};
+use ephemeral_vrf_sdk::types::SerializableAccountMeta;
+
+use crate::error::ErStateError;
+use crate::state::{move_lamports, House, Wager, WagerStatus, WIN_MULTIPLIER};
+use crate::{instruction, ID};
+
+#[vrf]
+#[derive(Accounts)]
+pub struct RollWager<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = user,
+        seeds = [b"wager", user.key().as_ref()],
+        bump = wager.bump,
+    )]
+    pub wager: Account<'info, Wager>,
+
+    #[account(
+        mut,
+        seeds = [b"house"],
+        bump = house.bump,
+    )]
+    pub house: Account<'info, House>,
+
+    /// CHECK: The oracle queue
+    #[account(
+        mut,
+        address = ephemeral_vrf_sdk::consts::DEFAULT_QUEUE
+    )]
+    pub oracle_queue: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WagerCallback<'info> {
+    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
+    pub vrf_program_identity: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"wager", wager.user.as_ref()],
+        bump = wager.bump,
+    )]
+    pub wager: Account<'info, Wager>,
+    /// CHECK: Receives the payout, checked against the wager
+    #[account(mut, address = wager.user)]
+    pub user: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"house"],
+        bump = house.bump,
+    )]
+    pub house: Account<'info, House>,
+}
+
+impl RollWager<'_> {
+    pub fn roll_wager(&mut self, client_seed: u8) -> Result<()> {
+        require!(
+            self.wager.status == WagerStatus::Open,
+            ErStateError::WagerNotOpen
+        );
+
+        // the house's share is locked in the wager so the callback can always pay out
+        let house_share = self.wager.house_share().ok_or(ErStateError::Overflow)?;
+        let house_rent = Rent::get()?.minimum_balance(8 + House::INIT_SPACE);
+        let house_needed = house_rent
+            .checked_add(house_share)
+            .ok_or(ErStateError::Overflow)?;
+        require!(
+            self.house.to_account_info().lamports() >= house_needed,
+            ErStateError::HouseUnderfunded
+        );
+        move_lamports(
+            &self.house.to_account_info(),
+            &self.wager.to_account_info(),
+            house_share,
+        )?;
+
+        let slot = Clock::get()?.slot;
+        let request_id = hashv(&[
+            b"wager",
+            self.user.key.as_ref(),
+            &slot.to_le_bytes(),
+            &[client_seed],
+        ])
+        .to_bytes();
+
+        self.wager.status = WagerStatus::Pending;
+        self.wager.request_id = request_id;
+        self.wager.requested_slot = slot;
+
+        msg!("Rolling the dice!");
+        let ixn = create_request_randomness_ix(RequestRandomnessParams {
+            payer: self.user.key(),
+            oracle_queue: self.oracle_queue.key(),
+            callback_program_id: ID,
+            callback_discriminator: instruction::WagerCallback::DISCRIMINATOR.to_vec(),
+            accounts_metas: Some(vec![
+                SerializableAccountMeta {
+                    pubkey: self.wager.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+                SerializableAccountMeta {
+                    pubkey: self.user.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+                SerializableAccountMeta {
+                    pubkey: self.house.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ]),
+            // handed back to the callback, which must match it to the pending roll
+            callback_args: Some(request_id.to_vec()),
+            caller_seed: request_id,
+            ..Default::default()
+        });
+
+        self.invoke_signed_vrf(&self.user.to_account_info(), &ixn)?;
+
+        Ok(())
+    }
+}
+
+impl WagerCallback<'_> {
+    pub fn resolve(&mut self, roll: u8, request_id: &[u8; 32]) -> Result<()> {
+        // a second callback, or one after a timeout refund, finds nothing pending
+        require!(
+            self.wager.status == WagerStatus::Pending,
+            ErStateError::WagerNotPending
+        );
+        // a late answer to an earlier roll can't settle the one pending now
+        require!(
+            &self.wager.request_id == request_id,
+            ErStateError::RequestMismatch
+        );
+
+        let pot = self
+            .wager
+            .stake
+            .checked_mul(WIN_MULTIPLIER)
+            .ok_or(ErStateError::Overflow)?;
+        let wager = self.wager.to_account_info();
+
+        if roll == self.wager.guess {
+            move_lamports(&wager, &self.user, pot)?;
+            self.wager.status = WagerStatus::Won;
+        } else {
+            move_lamports(&wager, &self.house.to_account_info(), pot)?;
+            self.wager.status = WagerStatus::Lost;
+        }
+        self.wager.roll = roll;
+
+        msg!("Rolled {}, guessed {}", roll, self.wager.guess);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::{anchor::commit, ephem::commit_and_undelegate_accounts};
+
+use crate::state::Balance;
+
+#[commit]
+#[derive(Accounts)]
+pub struct SettleBalance<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"balance", balance.mint.as_ref(), owner.key().as_ref()],
+        bump = balance.bump,
+    )]
+    pub balance: Account<'info, Balance>,
+}
+
+impl<'info> SettleBalance<'info> {
+    /// Commits the balance as the ER left it and returns it to the base layer,
+    /// where `withdraw` pays it out of the vault.
+    pub fn settle_balance(&mut self) -> Result<()> {
+        commit_and_undelegate_accounts(
+            &self.owner.to_account_info(),
+            vec![&self.balance.to_account_info()],
+            &self.magic_context,
+            &self.magic_program,
+        )?;
+
+        Ok(())
+    }
+}
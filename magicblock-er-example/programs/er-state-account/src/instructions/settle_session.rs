@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::{anchor::commit, ephem::commit_and_undelegate_accounts};
+
+use crate::error::ErStateError;
+use crate::state::{GameSession, SessionStatus};
+
+#[commit]
+#[derive(Accounts)]
+pub struct SettleSession<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"session", session.host.as_ref(), &session.session_id.to_le_bytes()],
+        bump = session.bump,
+    )]
+    pub session: Account<'info, GameSession>,
+}
+
+impl<'info> SettleSession<'info> {
+    /// Ends the match on the ER, committing the final scores back to the base layer.
+    /// Any joined player can settle, so the session isn't stuck if the host leaves.
+    pub fn settle_session(&mut self) -> Result<()> {
+        require!(
+            self.session.status == SessionStatus::Active,
+            ErStateError::SessionNotActive
+        );
+        require!(
+            self.session.is_player(self.player.key),
+            ErStateError::NotAPlayer
+        );
+
+        self.session.status = SessionStatus::Settled;
+        self.session.exit(&crate::ID)?;
+
+        commit_and_undelegate_accounts(
+            &self.player.to_account_info(),
+            vec![&self.session.to_account_info()],
+            &self.magic_context,
+            &self.magic_program,
+        )?;
+
+        Ok(())
+    }
+}
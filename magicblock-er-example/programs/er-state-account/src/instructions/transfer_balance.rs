@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErStateError;
+use crate::state::Balance;
+
+#[derive(Accounts)]
+pub struct TransferBalance<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"balance", balance.mint.as_ref(), owner.key().as_ref()],
+        bump = balance.bump,
+    )]
+    pub balance: Account<'info, Balance>,
+    // derived from the sender's mint, so both sides are claims on the same vault
+    #[account(
+        mut,
+        seeds = [b"balance", balance.mint.as_ref(), recipient.owner.as_ref()],
+        bump = recipient.bump,
+    )]
+    pub recipient: Account<'info, Balance>,
+}
+
+impl<'info> TransferBalance<'info> {
+    /// Moves `amount` between two balances delegated to the ER, no token CPI involved.
+    pub fn transfer_balance(&mut self, amount: u64) -> Result<()> {
+        require!(
+            amount > 0 && self.balance.key() != self.recipient.key(),
+            ErStateError::InvalidTransfer
+        );
+
+        self.balance.amount = self
+            .balance
+            .amount
+            .checked_sub(amount)
+            .ok_or(ErStateError::InsufficientBalance)?;
+        self.recipient.amount = self
+            .recipient
+            .amount
+            .checked_add(amount)
+            .ok_or(ErStateError::Overflow)?;
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 use ephemeral_rollups_sdk::{anchor::commit, ephem::commit_and_undelegate_accounts};
 
-use crate::state::UserAccount;
+use crate::state::{PendingRandomness, UserAccount};
 
 #[commit]
 #[derive(Accounts)]
@@ -15,17 +15,27 @@ pub struct Undelegate<'info> {
         bump = user_account.bump,
     )]
     pub user_account: Account<'info, UserAccount>,
+    #[account(
+        mut,
+        seeds = [b"randomness", user.key().as_ref()],
+        bump = pending_randomness.bump,
+    )]
+    pub pending_randomness: Account<'info, PendingRandomness>,
 }
 
 impl<'info> Undelegate<'info> {
     
     pub fn undelegate(&mut self) -> Result<()> {
 
-        //self.user_account.exit(&crate::ID)?;
+        self.user_account.end_delegation(Clock::get()?.slot);
+        self.user_account.exit(&crate::ID)?;
 
         commit_and_undelegate_accounts(
             &self.user.to_account_info(), 
-            vec![&self.user_account.to_account_info()], 
+            vec![
+                &self.user_account.to_account_info(),
+                &self.pending_randomness.to_account_info(),
+            ],
             &self.magic_context, 
             &self.magic_program
         )?;
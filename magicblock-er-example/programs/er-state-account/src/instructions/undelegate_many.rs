@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::{anchor::commit, ephem::commit_and_undelegate_accounts};
+
+use crate::error::ErStateError;
+use crate::state::{DelegatedPda, UserAccount};
+
+#[commit]
+#[derive(Accounts)]
+pub struct UndelegateMany<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+impl<'info> UndelegateMany<'info> {
+    /// Commits and undelegates every PDA in `pdas`, passed in the same order as remaining accounts.
+    pub fn undelegate_many(
+        &mut self,
+        pdas: &[DelegatedPda],
+        remaining: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(
+            remaining.len() == pdas.len(),
+            ErStateError::AccountCountMismatch
+        );
+        let slot = Clock::get()?.slot;
+        for (pda, account) in pdas.iter().zip(remaining) {
+            pda.verify(self.user.key, account)?;
+
+            if let DelegatedPda::User = pda {
+                let mut user_account = Account::<UserAccount>::try_from(account)?;
+                user_account.end_delegation(slot);
+                user_account.exit(&crate::ID)?;
+            }
+        }
+
+        commit_and_undelegate_accounts(
+            &self.user.to_account_info(),
+            remaining.iter().collect(),
+            &self.magic_context,
+            &self.magic_program,
+        )?;
+
+        Ok(())
+    }
+}
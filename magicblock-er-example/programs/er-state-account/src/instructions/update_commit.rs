@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use ephemeral_rollups_sdk::{anchor::commit, ephem::commit_accounts};
 
-use crate::state::UserAccount;
+use crate::state::{UserAccount, UserOp};
 
 #[commit]
 #[derive(Accounts)]
@@ -18,10 +18,12 @@ pub struct UpdateCommit<'info> {
 
 impl<'info> UpdateCommit<'info> {
     
-    pub fn update_commit(&mut self, new_data: u64) -> Result<()> {
+    pub fn update_commit(&mut self, op: UserOp) -> Result<()> {
 
-        // Update the data field
-        self.user_account.data = new_data;
+        let slot = Clock::get()?.slot;
+        self.user_account.apply(&op, slot)?;
+        self.user_account.record_commit(slot);
+        self.user_account.exit(&crate::ID)?;
 
         commit_accounts(
             &self.user.to_account_info(), 
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErStateError;
+use crate::state::{Inventory, KEY_LEN};
+
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct UpdateInventory<'info> {
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"inventory", user.key().as_ref(), &[index]],
+        bump = inventory.bump,
+    )]
+    pub inventory: Account<'info, Inventory>,
+}
+
+impl<'info> UpdateInventory<'info> {
+    pub fn update_inventory(&mut self, item: [u8; KEY_LEN], quantity: u64) -> Result<()> {
+        require!(item != [0; KEY_LEN], ErStateError::InvalidKey);
+        self.inventory
+            .set_item(item, quantity)
+            .ok_or(ErStateError::InventoryFull)?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,15 @@
 use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::{
+    consts::{MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID},
+    ephem::commit_accounts,
+};
 
-use crate::state::UserAccount;
+use crate::error::ErStateError;
+use crate::state::{UserAccount, UserOp};
 
 #[derive(Accounts)]
 pub struct UpdateUser<'info> {
+    #[account(mut)]
     pub user: Signer<'info>,
     #[account(
         mut,
@@ -14,11 +20,40 @@ pub struct UpdateUser<'info> {
 }
 
 impl<'info> UpdateUser<'info> {
-    pub fn update(&mut self, new_data: u64) -> Result<()> {
+    /// Applies `op`; once the commit policy is due the update also commits, taking
+    /// `[magic_context, magic_program]` as remaining accounts.
+    pub fn update(&mut self, op: UserOp, remaining: &'info [AccountInfo<'info>]) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        self.user_account.apply(&op, slot)?;
+
+        if !self.user_account.commit_due(slot) {
+            return Ok(());
+        }
+
+        let [magic_context, magic_program, ..] = remaining else {
+            return err!(ErStateError::CommitRequired);
+        };
+        require_keys_eq!(
+            magic_context.key(),
+            MAGIC_CONTEXT_ID,
+            ErStateError::CommitRequired
+        );
+        require_keys_eq!(
+            magic_program.key(),
+            MAGIC_PROGRAM_ID,
+            ErStateError::CommitRequired
+        );
+
+        self.user_account.record_commit(slot);
+        self.user_account.exit(&crate::ID)?;
+
+        commit_accounts(
+            &self.user.to_account_info(),
+            vec![&self.user_account.to_account_info()],
+            magic_context,
+            magic_program,
+        )?;
 
-        // Update the data field
-        self.user_account.data = new_data;
-        
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -3,7 +3,9 @@ use ephemeral_vrf_sdk::anchor::vrf;
 use ephemeral_vrf_sdk::instructions::{create_request_randomness_ix, RequestRandomnessParams};
 use ephemeral_vrf_sdk::types::SerializableAccountMeta;
 
-use crate::state::UserAccount;
+use crate::error::ErStateError;
+use crate::randomness::RandomStream;
+use crate::state::{PendingRandomness, RandomRequest, StateKey, UserAccount, UserOp, MAX_ROLLS};
 use crate::{instruction, ID};
 
 #[vrf]
@@ -18,6 +20,13 @@ pub struct VrfRandomBase<'info> {
     )]
     pub user_account: Account<'info, UserAccount>,
 
+    #[account(
+        mut,
+        seeds=[b"randomness", user.key().as_ref()],
+        bump= pending_randomness.bump
+    )]
+    pub pending_randomness: Account<'info, PendingRandomness>,
+
     /// CHECK: The oracle queue
     #[account(
         mut,
@@ -38,6 +47,13 @@ pub struct VrfRandomEr<'info> {
     )]
     pub user_account: Account<'info, UserAccount>,
 
+    #[account(
+        mut,
+        seeds=[b"randomness", user.key().as_ref()],
+        bump= pending_randomness.bump
+    )]
+    pub pending_randomness: Account<'info, PendingRandomness>,
+
     /// CHECK: The oracle queue
     #[account(
         mut,
@@ -50,24 +66,48 @@ pub struct VrfRandomEr<'info> {
 pub struct VrfCallback<'info> {
     #[account(address=ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
     pub vrf_program_identity: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds=[b"user", user_account.user.as_ref()],
+        bump= user_account.bump
+    )]
     pub user_account: Account<'info, UserAccount>,
+    #[account(
+        mut,
+        seeds=[b"randomness", user_account.user.as_ref()],
+        bump= pending_randomness.bump
+    )]
+    pub pending_randomness: Account<'info, PendingRandomness>,
 }
 
 impl VrfRandomBase<'_> {
-    pub fn vrf_random_base(&mut self, client_seed: u8) -> Result<()> {
+    pub fn vrf_random_base(&mut self, client_seed: u8, request: RandomRequest) -> Result<()> {
+        self.user_account.random.check(&request)?;
+        let seed = self
+            .pending_randomness
+            .open(Clock::get()?.slot, client_seed, request)?;
+
         msg!("Requesting a random number!");
         let ixn = create_request_randomness_ix(RequestRandomnessParams {
             payer: self.user.key(),
             oracle_queue: self.oracle_queue.key(),
             callback_program_id: ID,
             callback_discriminator: instruction::VrfCallback::DISCRIMINATOR.to_vec(),
-            accounts_metas: Some(vec![SerializableAccountMeta {
-                pubkey: self.user_account.key(),
-                is_signer: false,
-                is_writable: true,
-            }]),
-            caller_seed: [client_seed; 32],
+            accounts_metas: Some(vec![
+                SerializableAccountMeta {
+                    pubkey: self.user_account.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+                SerializableAccountMeta {
+                    pubkey: self.pending_randomness.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ]),
+            // handed back to the callback, which must match it to the pending request
+            callback_args: Some(seed.to_vec()),
+            caller_seed: seed,
             ..Default::default()
         });
 
@@ -78,19 +118,33 @@ impl VrfRandomBase<'_> {
 }
 
 impl VrfRandomEr<'_> {
-    pub fn vrf_random_er(&mut self, client_seed: u8) -> Result<()> {
+    pub fn vrf_random_er(&mut self, client_seed: u8, request: RandomRequest) -> Result<()> {
+        self.user_account.random.check(&request)?;
+        let seed = self
+            .pending_randomness
+            .open(Clock::get()?.slot, client_seed, request)?;
+
         msg!("Requesting a random number!");
         let ixn = create_request_randomness_ix(RequestRandomnessParams {
             payer: self.user.key(),
             oracle_queue: self.oracle_queue.key(),
             callback_program_id: ID,
             callback_discriminator: instruction::VrfCallback::DISCRIMINATOR.to_vec(),
-            accounts_metas: Some(vec![SerializableAccountMeta {
-                pubkey: self.user_account.key(),
-                is_signer: false,
-                is_writable: true,
-            }]),
-            caller_seed: [client_seed; 32],
+            accounts_metas: Some(vec![
+                SerializableAccountMeta {
+                    pubkey: self.user_account.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+                SerializableAccountMeta {
+                    pubkey: self.pending_randomness.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ]),
+            // handed back to the callback, which must match it to the pending request
+            callback_args: Some(seed.to_vec()),
+            caller_seed: seed,
             ..Default::default()
         });
 
@@ -101,8 +155,66 @@ impl VrfRandomEr<'_> {
 }
 
 impl VrfCallback<'_> {
-    pub fn update_user(&mut self, rnd_u8: u8) -> Result<()> {
-        self.user_account.data = rnd_u8 as u64;
+    /// Draws what the pending request asked for from `randomness`.
+    pub fn draw(&mut self, randomness: [u8; 32], seed: &[u8; 32]) -> Result<()> {
+        self.pending_randomness.consume(seed)?;
+        let slot = Clock::get()?.slot;
+        let user_account = &mut self.user_account;
+        let mut stream = RandomStream::new(randomness);
+        let mut rolls = [0u64; MAX_ROLLS];
+
+        let count = match self.pending_randomness.request {
+            RandomRequest::Dice => {
+                let roll = ephemeral_vrf_sdk::rnd::random_u8_with_range(&randomness, 1, 6);
+                user_account.data = roll as u64;
+                rolls[0] = roll as u64;
+                1
+            }
+            RandomRequest::Range { min, max, count } => {
+                for roll in &mut rolls[..count as usize] {
+                    *roll = stream.range_u64(min, max);
+                }
+                count as usize
+            }
+            RandomRequest::Weighted { count } => {
+                let weights = user_account.random.weights;
+                for roll in &mut rolls[..count as usize] {
+                    // the table may have been cleared since the request
+                    let index = stream
+                        .weighted_index(&weights)
+                        .ok_or(ErStateError::InvalidRandomRequest)?;
+                    *roll = index as u64;
+                }
+                count as usize
+            }
+            RandomRequest::Shuffle => {
+                let len = user_account.random.list_len as usize;
+                stream.shuffle(&mut user_account.random.list[..len]);
+                0
+            }
+            RandomRequest::Loot { rolls: count } => {
+                let weights = user_account.random.loot_weights();
+                for roll in &mut rolls[..count as usize] {
+                    let index = stream
+                        .weighted_index(&weights)
+                        .ok_or(ErStateError::InvalidRandomRequest)?;
+                    let entry = user_account.random.loot[index];
+                    let amount = stream.range_u64(entry.min as u64, entry.max as u64);
+                    user_account.apply(
+                        &UserOp::Increment {
+                            key: StateKey::Entry { key: entry.item },
+                            amount,
+                        },
+                        slot,
+                    )?;
+                    *roll = index as u64;
+                }
+                count as usize
+            }
+        };
+
+        user_account.random.record(&rolls[..count]);
+        user_account.last_updated_slot = slot;
 
         Ok(())
     }
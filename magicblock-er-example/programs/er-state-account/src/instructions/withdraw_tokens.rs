@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::error::ErStateError;
+use crate::state::{Balance, TokenVault};
+
+#[derive(Accounts)]
+pub struct WithdrawTokens<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, TokenVault>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+    // owned by this program again only once the settlement reached the base layer
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = mint,
+        seeds = [b"balance", mint.key().as_ref(), owner.key().as_ref()],
+        bump = balance.bump,
+    )]
+    pub balance: Account<'info, Balance>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawTokens<'info> {
+    /// Pays `amount` of the committed balance out of the vault, checking the vault
+    /// still backs every balance of the mint afterwards.
+    pub fn withdraw(&mut self, amount: u64) -> Result<()> {
+        self.balance.amount = self
+            .balance
+            .amount
+            .checked_sub(amount)
+            .ok_or(ErStateError::InsufficientBalance)?;
+        self.vault.total_balances = self
+            .vault
+            .total_balances
+            .checked_sub(amount)
+            .ok_or(ErStateError::VaultShortfall)?;
+
+        let mint = self.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint.as_ref(), &[self.vault.bump]]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault_ata.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to: self.owner_ata.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            self.mint.decimals,
+        )?;
+
+        self.vault_ata.reload()?;
+        require!(
+            self.vault_ata.amount >= self.vault.total_balances,
+            ErStateError::VaultShortfall
+        );
+
+        Ok(())
+    }
+}
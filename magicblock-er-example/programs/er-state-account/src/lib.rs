@@ -4,10 +4,14 @@
 use anchor_lang::prelude::*;
 use ephemeral_rollups_sdk::anchor::ephemeral;
 
+mod error;
 mod instructions;
+mod randomness;
 mod state;
+mod tests;
 
 use instructions::*;
+use state::{CommitPolicy, DelegatedPda, LootEntry, RandomRequest, UserOp, KEY_LEN};
 
 declare_id!("9qBxscSpFJNuQScvbR4Bc8KrV1iAgRmdUUjfeNf26k7H");
 
@@ -23,20 +27,29 @@ pub mod er_state_account {
         Ok(())
     }
 
-    pub fn update(ctx: Context<UpdateUser>, new_data: u64) -> Result<()> {
-        ctx.accounts.update(new_data)?;
+    pub fn update<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateUser<'info>>,
+        op: UserOp,
+    ) -> Result<()> {
+        ctx.accounts.update(op, ctx.remaining_accounts)?;
 
         Ok(())
     }
 
-    pub fn update_commit(ctx: Context<UpdateCommit>, new_data: u64) -> Result<()> {
-        ctx.accounts.update_commit(new_data)?;
+    pub fn update_commit(ctx: Context<UpdateCommit>, op: UserOp) -> Result<()> {
+        ctx.accounts.update_commit(op)?;
 
         Ok(())
     }
 
-    pub fn delegate(ctx: Context<Delegate>) -> Result<()> {
-        ctx.accounts.delegate()?;
+    pub fn resize(ctx: Context<ResizeUser>, capacity: u16) -> Result<()> {
+        ctx.accounts.resize(capacity)?;
+
+        Ok(())
+    }
+
+    pub fn delegate(ctx: Context<Delegate>, policy: CommitPolicy) -> Result<()> {
+        ctx.accounts.delegate(policy)?;
 
         Ok(())
     }
@@ -47,22 +60,192 @@ pub mod er_state_account {
         Ok(())
     }
 
+    pub fn init_inventory(ctx: Context<InitInventory>, index: u8) -> Result<()> {
+        ctx.accounts.init_inventory(index, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn update_inventory(
+        ctx: Context<UpdateInventory>,
+        _index: u8,
+        item: [u8; KEY_LEN],
+        quantity: u64,
+    ) -> Result<()> {
+        ctx.accounts.update_inventory(item, quantity)?;
+
+        Ok(())
+    }
+
+    pub fn delegate_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DelegateMany<'info>>,
+        pdas: Vec<DelegatedPda>,
+    ) -> Result<()> {
+        ctx.accounts.delegate_many(&pdas, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn undelegate_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UndelegateMany<'info>>,
+        pdas: Vec<DelegatedPda>,
+    ) -> Result<()> {
+        ctx.accounts.undelegate_many(&pdas, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_id: u64,
+        max_players: u8,
+    ) -> Result<()> {
+        ctx.accounts
+            .create_session(session_id, max_players, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn join_session(ctx: Context<JoinSession>) -> Result<()> {
+        ctx.accounts.join_session()?;
+
+        Ok(())
+    }
+
+    pub fn delegate_session(ctx: Context<DelegateSession>) -> Result<()> {
+        ctx.accounts.delegate()?;
+
+        Ok(())
+    }
+
+    pub fn make_move(ctx: Context<MakeMove>, points: u64) -> Result<()> {
+        ctx.accounts.make_move(points)?;
+
+        Ok(())
+    }
+
+    pub fn settle_session(ctx: Context<SettleSession>) -> Result<()> {
+        ctx.accounts.settle_session()?;
+
+        Ok(())
+    }
+
+    pub fn fund_house(ctx: Context<FundHouse>, amount: u64) -> Result<()> {
+        ctx.accounts.fund_house(amount, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn place_wager(ctx: Context<PlaceWager>, stake: u64, guess: u8) -> Result<()> {
+        ctx.accounts.place_wager(stake, guess, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn roll_wager(ctx: Context<RollWager>, client_seed: u8) -> Result<()> {
+        ctx.accounts.roll_wager(client_seed)?;
+
+        Ok(())
+    }
+
+    pub fn wager_callback(
+        ctx: Context<WagerCallback>,
+        randomness: [u8; 32],
+        request_id: [u8; 32],
+    ) -> Result<()> {
+        let roll = ephemeral_vrf_sdk::rnd::random_u8_with_range(&randomness, 1, 6);
+        ctx.accounts.resolve(roll, &request_id)?;
+
+        Ok(())
+    }
+
+    pub fn refund_wager(ctx: Context<RefundWager>) -> Result<()> {
+        ctx.accounts.refund_wager()?;
+
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<DepositTokens>, amount: u64) -> Result<()> {
+        ctx.accounts.deposit(amount, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn delegate_balance(ctx: Context<DelegateBalance>) -> Result<()> {
+        ctx.accounts.delegate()?;
+
+        Ok(())
+    }
+
+    pub fn transfer_balance(ctx: Context<TransferBalance>, amount: u64) -> Result<()> {
+        ctx.accounts.transfer_balance(amount)?;
+
+        Ok(())
+    }
+
+    pub fn settle_balance(ctx: Context<SettleBalance>) -> Result<()> {
+        ctx.accounts.settle_balance()?;
+
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<WithdrawTokens>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw(amount)?;
+
+        Ok(())
+    }
+
     pub fn close(ctx: Context<CloseUser>) -> Result<()> {
         ctx.accounts.close()?;
 
         Ok(())
     }
 
-    pub fn vrf_random_base(ctx: Context<VrfRandomBase>, client_seed: u8) -> Result<()> {
-        ctx.accounts.vrf_random_base(client_seed)
+    pub fn set_weights(ctx: Context<ConfigureRandom>, weights: Vec<u32>) -> Result<()> {
+        ctx.accounts.set_weights(&weights)?;
+
+        Ok(())
     }
 
-    pub fn vrf_random_er(ctx: Context<VrfRandomEr>, client_seed: u8) -> Result<()> {
-        ctx.accounts.vrf_random_er(client_seed)
+    pub fn set_list(ctx: Context<ConfigureRandom>, list: Vec<u8>) -> Result<()> {
+        ctx.accounts.set_list(&list)?;
+
+        Ok(())
     }
 
-    pub fn vrf_callback(ctx: Context<VrfCallback>, randomness: [u8; 32]) -> Result<()> {
-        let rnd_u8 = ephemeral_vrf_sdk::rnd::random_u8_with_range(&randomness, 1, 6);
-        ctx.accounts.update_user(rnd_u8)
+    pub fn set_loot_table(ctx: Context<ConfigureRandom>, loot: Vec<LootEntry>) -> Result<()> {
+        ctx.accounts.set_loot_table(&loot)?;
+
+        Ok(())
+    }
+
+    pub fn vrf_random_base(
+        ctx: Context<VrfRandomBase>,
+        client_seed: u8,
+        request: RandomRequest,
+    ) -> Result<()> {
+        ctx.accounts.vrf_random_base(client_seed, request)?;
+
+        Ok(())
+    }
+
+    pub fn vrf_random_er(
+        ctx: Context<VrfRandomEr>,
+        client_seed: u8,
+        request: RandomRequest,
+    ) -> Result<()> {
+        ctx.accounts.vrf_random_er(client_seed, request)?;
+
+        Ok(())
+    }
+
+    pub fn vrf_callback(
+        ctx: Context<VrfCallback>,
+        randomness: [u8; 32],
+        seed: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.draw(randomness, &seed)?;
+
+        Ok(())
     }
 }
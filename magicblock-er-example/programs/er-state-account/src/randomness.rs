@@ -0,0 +1,64 @@
+use anchor_lang::solana_program::hash::hashv;
+
+/// Stretches one 32-byte VRF output into as many u64 words as a callback needs.
+/// Word `i` is the first 8 bytes of `sha256(randomness || i)`, so every draw is
+/// reproducible from the randomness the oracle published.
+pub struct RandomStream {
+    randomness: [u8; 32],
+    counter: u64,
+}
+
+impl RandomStream {
+    pub fn new(randomness: [u8; 32]) -> Self {
+        Self {
+            randomness,
+            counter: 0,
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let hash = hashv(&[&self.randomness, &self.counter.to_le_bytes()]).to_bytes();
+        self.counter += 1;
+        u64::from_le_bytes(hash[..8].try_into().unwrap())
+    }
+
+    /// Uniform value in `min..=max`. Words below `2^64 mod span` are redrawn,
+    /// which keeps the modulo free of bias.
+    pub fn range_u64(&mut self, min: u64, max: u64) -> u64 {
+        let span = (max - min).wrapping_add(1);
+        if span == 0 {
+            return self.next_u64(); // the full u64 range
+        }
+
+        let threshold = span.wrapping_neg() % span;
+        loop {
+            let word = self.next_u64();
+            if word >= threshold {
+                return min + word % span;
+            }
+        }
+    }
+
+    /// Index drawn with probability proportional to its weight, `None` if all are zero.
+    pub fn weighted_index(&mut self, weights: &[u32]) -> Option<usize> {
+        let total: u64 = weights.iter().map(|w| *w as u64).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut pick = self.range_u64(0, total - 1);
+        weights.iter().position(|w| {
+            let hit = pick < *w as u64;
+            pick = pick.saturating_sub(*w as u64);
+            hit
+        })
+    }
+
+    /// Fisher-Yates shuffle in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range_u64(0, i as u64) as usize;
+            items.swap(i, j);
+        }
+    }
+}
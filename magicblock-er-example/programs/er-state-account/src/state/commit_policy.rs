@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+/// When a delegated account has to commit back to the base layer, set on `delegate`.
+/// A zero bound is disabled; with both zero only `update_commit` commits.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, InitSpace, PartialEq, Eq,
+)]
+pub struct CommitPolicy {
+    pub every_updates: u32,
+    pub every_slots: u64,
+}
+
+impl CommitPolicy {
+    pub fn is_due(&self, updates_since_commit: u32, slots_since_commit: u64) -> bool {
+        (self.every_updates != 0 && updates_since_commit >= self.every_updates)
+            || (self.every_slots != 0 && slots_since_commit >= self.every_slots)
+    }
+}
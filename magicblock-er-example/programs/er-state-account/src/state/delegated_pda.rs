@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+/// A user-owned PDA of this program, named by its seeds, for batch (un)delegation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DelegatedPda {
+    User,
+    Randomness,
+    Inventory { index: u8 },
+}
+
+impl DelegatedPda {
+    pub fn seeds(&self, user: &Pubkey) -> Vec<Vec<u8>> {
+        match self {
+            DelegatedPda::User => vec![b"user".to_vec(), user.to_bytes().to_vec()],
+            DelegatedPda::Randomness => vec![b"randomness".to_vec(), user.to_bytes().to_vec()],
+            DelegatedPda::Inventory { index } => vec![
+                b"inventory".to_vec(),
+                user.to_bytes().to_vec(),
+                vec![*index],
+            ],
+        }
+    }
+
+    /// Checks that `account` is the PDA these seeds derive for `user`.
+    pub fn verify(&self, user: &Pubkey, account: &AccountInfo) -> Result<Vec<Vec<u8>>> {
+        let seeds = self.seeds(user);
+        let seed_slices: Vec<&[u8]> = seeds.iter().map(|s| s.as_slice()).collect();
+        let (pda, _) = Pubkey::find_program_address(&seed_slices, &crate::ID);
+        require_keys_eq!(pda, account.key(), crate::error::ErStateError::InvalidPda);
+
+        Ok(seeds)
+    }
+}
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+/// Where and since when a `UserAccount` is delegated. Kept after `undelegate`
+/// as the record of the last delegation, with `delegated` cleared.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, InitSpace, PartialEq, Eq,
+)]
+pub struct DelegationInfo {
+    pub delegated: bool,
+    pub validator: Pubkey,
+    pub delegated_at_slot: u64,
+    pub commit_count: u64, // commits since `delegated_at_slot`, the undelegating one included
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_PLAYERS: usize = 4;
+/// Upper bound on the points a single move can score.
+pub const MAX_POINTS_PER_MOVE: u64 = 100;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum SessionStatus {
+    /// Players can join, on the base layer.
+    Open,
+    /// Delegated to the ER validator, players are moving.
+    Active,
+    /// Committed and undelegated, final scores on the base layer.
+    Settled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, InitSpace)]
+pub struct PlayerState {
+    pub player: Pubkey,
+    pub score: u64,
+    pub moves: u32,
+}
+
+/// A match shared by up to `MAX_PLAYERS` players, delegated as a whole to the ER.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct GameSession {
+    pub host: Pubkey,
+    pub session_id: u64,
+    pub bump: u8,
+    pub status: SessionStatus,
+    pub max_players: u8,
+    pub player_count: u8,
+    pub players: [PlayerState; MAX_PLAYERS], // host first, in join order
+    pub move_count: u64,
+    pub last_move_slot: u64,
+}
+
+impl GameSession {
+    pub fn is_player(&self, player: &Pubkey) -> bool {
+        self.players[..self.player_count as usize]
+            .iter()
+            .any(|p| &p.player == player)
+    }
+
+    pub fn player_mut(&mut self, player: &Pubkey) -> Option<&mut PlayerState> {
+        self.players[..self.player_count as usize]
+            .iter_mut()
+            .find(|p| &p.player == player)
+    }
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Counter, KEY_LEN};
+
+pub const MAX_ITEMS: usize = 8;
+
+/// Item stacks owned by a user, one of several PDAs delegated along with its `UserAccount`.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Inventory {
+    pub user: Pubkey,
+    pub index: u8,
+    pub bump: u8,
+    pub items: [Counter; MAX_ITEMS], // item name + quantity, zero name = free slot
+}
+
+impl Inventory {
+    /// Sets the quantity of `item`, a zero quantity frees its slot.
+    pub fn set_item(&mut self, item: [u8; KEY_LEN], quantity: u64) -> Option<()> {
+        let slot = match self.items.iter().position(|i| i.name == item) {
+            Some(slot) => slot,
+            None if quantity == 0 => return Some(()),
+            None => self.items.iter().position(|i| i.name == [0; KEY_LEN])?,
+        };
+
+        self.items[slot] = if quantity == 0 {
+            Counter::default()
+        } else {
+            Counter {
+                name: item,
+                value: quantity,
+            }
+        };
+
+        Some(())
+    }
+}
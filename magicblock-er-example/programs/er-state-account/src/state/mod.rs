@@ -1,3 +1,23 @@
+pub mod commit_policy;
+pub mod delegated_pda;
+pub mod delegation_info;
+pub mod game_session;
+pub mod inventory;
+pub mod pending_randomness;
+pub mod random_state;
+pub mod token_balance;
 pub mod user_account;
+pub mod user_op;
+pub mod wager;
 
-pub use user_account::*;
\ No newline at end of file
+pub use commit_policy::*;
+pub use delegated_pda::*;
+pub use delegation_info::*;
+pub use game_session::*;
+pub use inventory::*;
+pub use pending_randomness::*;
+pub use random_state::*;
+pub use token_balance::*;
+pub use user_account::*;
+pub use user_op::*;
+pub use wager::*;
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::error::ErStateError;
+use crate::state::RandomRequest;
+
+/// Slots after which an unanswered request can be replaced by a new one.
+pub const REQUEST_TIMEOUT_SLOTS: u64 = 150;
+
+/// The user's outstanding VRF request. The callback has to present its seed,
+/// so only the latest request of this user can write to their account, once.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct PendingRandomness {
+    pub user: Pubkey,
+    pub bump: u8,
+    pub pending: bool,
+    pub seed: [u8; 32], // VRF caller seed of the outstanding request
+    pub slot: u64,
+    pub nonce: u64,             // bumped on every request, so no seed repeats
+    pub request: RandomRequest, // what the callback draws
+}
+
+impl PendingRandomness {
+    /// Records a new request and returns its caller seed,
+    /// `sha256("randomness" || user || slot || nonce || client_seed)`.
+    pub fn open(&mut self, slot: u64, client_seed: u8, request: RandomRequest) -> Result<[u8; 32]> {
+        require!(
+            !self.pending || slot >= self.slot.saturating_add(REQUEST_TIMEOUT_SLOTS),
+            ErStateError::RequestPending
+        );
+
+        self.nonce = self.nonce.checked_add(1).ok_or(ErStateError::Overflow)?;
+        self.seed = hashv(&[
+            b"randomness",
+            self.user.as_ref(),
+            &slot.to_le_bytes(),
+            &self.nonce.to_le_bytes(),
+            &[client_seed],
+        ])
+        .to_bytes();
+        self.slot = slot;
+        self.pending = true;
+        self.request = request;
+
+        Ok(self.seed)
+    }
+
+    /// Checks the callback answers the outstanding request and marks it answered.
+    pub fn consume(&mut self, seed: &[u8; 32]) -> Result<()> {
+        require!(self.pending, ErStateError::NoPendingRequest);
+        require!(&self.seed == seed, ErStateError::RequestMismatch);
+        self.pending = false;
+
+        Ok(())
+    }
+}
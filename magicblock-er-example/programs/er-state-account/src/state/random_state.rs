@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErStateError;
+use crate::state::KEY_LEN;
+
+pub const MAX_WEIGHTS: usize = 8;
+pub const MAX_LIST: usize = 16;
+pub const MAX_LOOT: usize = 8;
+/// Values a single callback can draw.
+pub const MAX_ROLLS: usize = 8;
+
+/// One row of the loot table, a roll adds `min..=max` of `item` to the user's map.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, InitSpace)]
+pub struct LootEntry {
+    pub item: [u8; KEY_LEN],
+    pub weight: u32,
+    pub min: u32,
+    pub max: u32,
+}
+
+/// What a VRF request draws once its randomness arrives.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, InitSpace)]
+pub enum RandomRequest {
+    /// A die face in 1..=6, written to `data`.
+    Dice,
+    /// `count` unbiased values in `min..=max`.
+    Range { min: u64, max: u64, count: u8 },
+    /// `count` indices into the weight table.
+    Weighted { count: u8 },
+    /// Shuffles the stored list.
+    Shuffle,
+    /// `rolls` draws from the loot table, credited to the map.
+    Loot { rolls: u8 },
+}
+
+/// Tables the randomness callbacks draw from, and what the last one drew.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, InitSpace)]
+pub struct RandomState {
+    pub weights: [u32; MAX_WEIGHTS], // a zero weight is never drawn
+    pub list_len: u8,
+    pub list: [u8; MAX_LIST],
+    pub loot: [LootEntry; MAX_LOOT], // zero weight = free row
+    pub roll_count: u8,
+    pub rolls: [u64; MAX_ROLLS],
+}
+
+impl RandomState {
+    pub fn set_weights(&mut self, weights: &[u32]) -> Result<()> {
+        require!(
+            weights.len() <= MAX_WEIGHTS,
+            ErStateError::InvalidRandomTable
+        );
+        self.weights = [0; MAX_WEIGHTS];
+        self.weights[..weights.len()].copy_from_slice(weights);
+
+        Ok(())
+    }
+
+    pub fn set_list(&mut self, list: &[u8]) -> Result<()> {
+        require!(list.len() <= MAX_LIST, ErStateError::InvalidRandomTable);
+        self.list = [0; MAX_LIST];
+        self.list[..list.len()].copy_from_slice(list);
+        self.list_len = list.len() as u8;
+
+        Ok(())
+    }
+
+    pub fn set_loot(&mut self, loot: &[LootEntry]) -> Result<()> {
+        require!(loot.len() <= MAX_LOOT, ErStateError::InvalidRandomTable);
+        for entry in loot {
+            require!(entry.item != [0; KEY_LEN], ErStateError::InvalidKey);
+            require!(
+                entry.weight > 0 && entry.min <= entry.max,
+                ErStateError::InvalidRandomTable
+            );
+        }
+        self.loot = [LootEntry::default(); MAX_LOOT];
+        self.loot[..loot.len()].copy_from_slice(loot);
+
+        Ok(())
+    }
+
+    pub fn loot_weights(&self) -> [u32; MAX_LOOT] {
+        self.loot.map(|entry| entry.weight)
+    }
+
+    /// Checks `request` can be answered from the current tables before paying for it.
+    pub fn check(&self, request: &RandomRequest) -> Result<()> {
+        let valid = match *request {
+            RandomRequest::Dice | RandomRequest::Shuffle => true,
+            RandomRequest::Range { min, max, count } => min <= max && is_roll_count(count),
+            RandomRequest::Weighted { count } => {
+                is_roll_count(count) && self.weights.iter().any(|w| *w > 0)
+            }
+            RandomRequest::Loot { rolls } => {
+                is_roll_count(rolls) && self.loot.iter().any(|e| e.weight > 0)
+            }
+        };
+        require!(valid, ErStateError::InvalidRandomRequest);
+
+        Ok(())
+    }
+
+    /// Replaces the previous draw with `rolls`.
+    pub fn record(&mut self, rolls: &[u64]) {
+        self.rolls = [0; MAX_ROLLS];
+        self.rolls[..rolls.len()].copy_from_slice(rolls);
+        self.roll_count = rolls.len() as u8;
+    }
+}
+
+fn is_roll_count(count: u8) -> bool {
+    count > 0 && count as usize <= MAX_ROLLS
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+/// Authority of a mint's vault token account, and the sum of every `Balance`
+/// of that mint. ER transfers move amounts between balances without changing
+/// the sum, so the vault only has to cover it.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct TokenVault {
+    pub mint: Pubkey,
+    pub bump: u8,
+    pub total_balances: u64,
+}
+
+/// A user's claim on a vault, delegated to transfer at ER speed.
+/// Deposits and withdrawals need it settled back on the base layer.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Balance {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub bump: u8,
+    pub amount: u64,
+}
@@ -1,12 +1,163 @@
 use anchor_lang::prelude::*;
 
+use crate::error::ErStateError;
+use crate::state::{CommitPolicy, DelegationInfo, RandomState, StateKey, UserOp};
+
+pub const KEY_LEN: usize = 16;
+pub const MAX_COUNTERS: usize = 4;
+/// Map entries allocated by `initialize`, `resize` grows or shrinks it.
+pub const DEFAULT_CAPACITY: u16 = 4;
+pub const MAX_CAPACITY: u16 = 64;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, InitSpace)]
+pub struct Counter {
+    pub name: [u8; KEY_LEN], // all zeroes while the slot is free
+    pub value: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, InitSpace)]
+pub struct Entry {
+    pub key: [u8; KEY_LEN],
+    pub value: u64,
+}
+
 #[account]
+#[derive(InitSpace, Debug)]
 pub struct UserAccount {
     pub user: Pubkey,
     pub data: u64,
     pub bump: u8,
+    pub last_updated_slot: u64,
+    pub commit_policy: CommitPolicy,
+    pub updates_since_commit: u32,
+    pub last_commit_slot: u64,
+    pub delegation: DelegationInfo,
+    pub counters: [Counter; MAX_COUNTERS],
+    pub random: RandomState,
+    pub capacity: u16,
+    #[max_len(0)] // room for `capacity` entries is added by `space`
+    pub entries: Vec<Entry>,
 }
 
-impl Space for UserAccount {
-    const INIT_SPACE: usize = 32 + 8 + 1 + 8; // Pubkey + u64 + u8 + 8 bytes for account discriminator
-}
\ No newline at end of file
+impl UserAccount {
+    /// Account size, discriminator included, for a map of `capacity` entries.
+    pub fn space(capacity: u16) -> usize {
+        8 + UserAccount::INIT_SPACE + capacity as usize * Entry::INIT_SPACE
+    }
+
+    /// Whether the commit policy requires committing, as of `slot`.
+    pub fn commit_due(&self, slot: u64) -> bool {
+        self.commit_policy.is_due(
+            self.updates_since_commit,
+            slot.saturating_sub(self.last_commit_slot),
+        )
+    }
+
+    pub fn record_commit(&mut self, slot: u64) {
+        self.updates_since_commit = 0;
+        self.last_commit_slot = slot;
+        self.delegation.commit_count = self.delegation.commit_count.saturating_add(1);
+    }
+
+    /// Opens a new delegation to `validator`, restarting the commit window.
+    pub fn start_delegation(&mut self, validator: Pubkey, slot: u64) {
+        self.delegation = DelegationInfo {
+            delegated: true,
+            validator,
+            delegated_at_slot: slot,
+            commit_count: 0,
+        };
+        self.updates_since_commit = 0;
+        self.last_commit_slot = slot;
+    }
+
+    /// Records the final commit of the delegation; back on the base layer
+    /// there is no magic program to commit to, so the policy is cleared too.
+    pub fn end_delegation(&mut self, slot: u64) {
+        self.record_commit(slot);
+        self.commit_policy = CommitPolicy::default();
+        self.delegation.delegated = false;
+    }
+
+    pub fn counter(&self, name: &[u8; KEY_LEN]) -> Option<u64> {
+        self.counters
+            .iter()
+            .find(|c| &c.name == name)
+            .map(|c| c.value)
+    }
+
+    pub fn entry(&self, key: &[u8; KEY_LEN]) -> Option<u64> {
+        self.entries.iter().find(|e| &e.key == key).map(|e| e.value)
+    }
+
+    /// Applies `op` and stamps the slot it happened in.
+    pub fn apply(&mut self, op: &UserOp, slot: u64) -> Result<()> {
+        match *op {
+            UserOp::Set { key, value } => *self.value_mut(&key, true)? = value,
+            UserOp::Increment { key, amount } => {
+                let value = self.value_mut(&key, true)?;
+                *value = value.checked_add(amount).ok_or(ErStateError::Overflow)?;
+            }
+            UserOp::Decrement { key, amount } => {
+                let value = self.value_mut(&key, false)?;
+                *value = value.checked_sub(amount).ok_or(ErStateError::Underflow)?;
+            }
+            UserOp::Reset { key } => match key {
+                // a reset entry is removed to free its room in the map
+                StateKey::Entry { key } => self.entries.retain(|e| e.key != key),
+                _ => {
+                    if let Ok(value) = self.value_mut(&key, false) {
+                        *value = 0;
+                    }
+                }
+            },
+        }
+
+        self.last_updated_slot = slot;
+        self.updates_since_commit = self.updates_since_commit.saturating_add(1);
+
+        Ok(())
+    }
+
+    /// Finds the value behind `key`, naming a free counter or inserting an entry if `create`.
+    fn value_mut(&mut self, key: &StateKey, create: bool) -> Result<&mut u64> {
+        match *key {
+            StateKey::Data => Ok(&mut self.data),
+            StateKey::Counter { name } => {
+                require!(name != [0; KEY_LEN], ErStateError::InvalidKey);
+                let found = self.counters.iter().position(|c| c.name == name);
+                let index = match found {
+                    Some(index) => index,
+                    None if create => {
+                        let index = self
+                            .counters
+                            .iter()
+                            .position(|c| c.name == [0; KEY_LEN])
+                            .ok_or(ErStateError::CountersFull)?;
+                        self.counters[index] = Counter { name, value: 0 };
+                        index
+                    }
+                    None => return err!(ErStateError::KeyNotFound),
+                };
+                Ok(&mut self.counters[index].value)
+            }
+            StateKey::Entry { key } => {
+                require!(key != [0; KEY_LEN], ErStateError::InvalidKey);
+                let found = self.entries.iter().position(|e| e.key == key);
+                let index = match found {
+                    Some(index) => index,
+                    None if create => {
+                        require!(
+                            self.entries.len() < self.capacity as usize,
+                            ErStateError::MapFull
+                        );
+                        self.entries.push(Entry { key, value: 0 });
+                        self.entries.len() - 1
+                    }
+                    None => return err!(ErStateError::KeyNotFound),
+                };
+                Ok(&mut self.entries[index].value)
+            }
+        }
+    }
+}
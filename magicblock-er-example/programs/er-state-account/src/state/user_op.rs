@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::state::KEY_LEN;
+
+/// Which value of a `UserAccount` an operation targets.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateKey {
+    Data,
+    Counter { name: [u8; KEY_LEN] },
+    Entry { key: [u8; KEY_LEN] },
+}
+
+/// Typed state change taken by `update` and `update_commit`.
+///
+/// `Set` and `Increment` name a free counter or insert a missing entry,
+/// `Decrement` requires the key to exist, `Reset` zeroes it (and removes map entries).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserOp {
+    Set { key: StateKey, value: u64 },
+    Increment { key: StateKey, amount: u64 },
+    Decrement { key: StateKey, amount: u64 },
+    Reset { key: StateKey },
+}
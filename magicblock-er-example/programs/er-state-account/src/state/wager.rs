@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+/// Total returned on a correct guess, in stakes; fair odds would be 6.
+pub const WIN_MULTIPLIER: u64 = 5;
+/// Slots after the roll request before an unanswered wager can be refunded.
+pub const WAGER_TIMEOUT_SLOTS: u64 = 150;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum WagerStatus {
+    /// Never used, or its last wager was settled.
+    Idle,
+    /// Stake placed, no randomness requested yet.
+    Open,
+    /// Randomness requested, waiting for the VRF callback.
+    Pending,
+    Won,
+    Lost,
+    Refunded,
+}
+
+/// A user's dice wager. The PDA holds the stake and, once rolled,
+/// the house's share of the payout so the callback can always settle.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Wager {
+    pub user: Pubkey,
+    pub bump: u8,
+    pub status: WagerStatus,
+    pub stake: u64,
+    pub guess: u8, // 1..=6
+    pub request_id: [u8; 32],
+    pub requested_slot: u64,
+    pub roll: u8, // 0 until resolved
+}
+
+impl Wager {
+    pub fn settled(&self) -> bool {
+        matches!(
+            self.status,
+            WagerStatus::Idle | WagerStatus::Won | WagerStatus::Lost | WagerStatus::Refunded
+        )
+    }
+
+    /// Lamports the house adds to the stake at roll time.
+    pub fn house_share(&self) -> Option<u64> {
+        self.stake.checked_mul(WIN_MULTIPLIER - 1)
+    }
+}
+
+/// Program-owned lamport pool paying out winning wagers.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct House {
+    pub bump: u8,
+}
+
+/// Moves lamports out of an account owned by this program.
+pub fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(crate::error::ErStateError::Underflow)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(crate::error::ErStateError::Overflow)?;
+
+    Ok(())
+}
@@ -0,0 +1,1227 @@
+#[cfg(test)]
+
+mod tests {
+    use std::path::PathBuf;
+
+    use anchor_lang::{
+        error::{ErrorCode, ERROR_CODE_OFFSET},
+        prelude::Pubkey,
+        AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use anchor_spl::associated_token::get_associated_token_address;
+    use ephemeral_rollups_sdk::{
+        consts::{DELEGATION_PROGRAM_ID, MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID},
+        pda::{
+            delegate_buffer_pda_from_delegated_account_and_owner_program,
+            delegation_metadata_pda_from_delegated_account,
+            delegation_record_pda_from_delegated_account,
+        },
+    };
+    use ephemeral_vrf_sdk::consts::{
+        DEFAULT_EPHEMERAL_QUEUE, DEFAULT_QUEUE, VRF_PROGRAM_ID, VRF_PROGRAM_IDENTITY,
+    };
+    use litesvm::{types::TransactionResult, LiteSVM};
+    use litesvm_token::{
+        get_spl_account, spl_token, CreateAssociatedTokenAccount, CreateMint, MintTo,
+    };
+    use solana_account::Account;
+    use solana_instruction::{error::InstructionError, AccountMeta, Instruction};
+    use solana_keypair::Keypair;
+    use solana_message::Message;
+    use solana_signer::Signer;
+    use solana_transaction::Transaction;
+    use solana_transaction_error::TransactionError;
+
+    use crate::{
+        error::ErStateError,
+        randomness::RandomStream,
+        state::{
+            Balance, CommitPolicy, GameSession, LootEntry, PendingRandomness, RandomRequest,
+            SessionStatus, StateKey, TokenVault, UserAccount, UserOp, Wager, WagerStatus, KEY_LEN,
+            MAX_POINTS_PER_MOVE, WAGER_TIMEOUT_SLOTS, WIN_MULTIPLIER,
+        },
+    };
+
+    /// Tag of the VRF stand-in's fulfil instruction, see `stubs/er-stubs`.
+    const FULFILL: [u8; 8] = *b"fulfill\0";
+
+    fn deploy_path(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../target/deploy")
+            .join(format!("{name}.so"))
+    }
+
+    /// One SVM plays both layers: delegated accounts are handed to the program
+    /// again by `enter_er`, where the validator would clone them into the rollup.
+    /// Undelegating leaves them with the program, as the delegation program's
+    /// callback does on the base layer.
+    fn setup() -> (LiteSVM, Keypair) {
+        let mut svm = LiteSVM::new();
+        let user = Keypair::new();
+        svm.airdrop(&user.pubkey(), 10_000_000_000)
+            .expect("Airdrop failed");
+
+        let program = std::fs::read(deploy_path("er_state_account"))
+            .expect("Failed to read program SO file, run `anchor build`");
+        svm.add_program(crate::ID, &program);
+
+        // cargo build-sbf --manifest-path stubs/er-stubs/Cargo.toml --sbf-out-dir target/deploy
+        let stubs = std::fs::read(deploy_path("er_stubs")).expect("Failed to read stubs SO file");
+        for id in [DELEGATION_PROGRAM_ID, MAGIC_PROGRAM_ID, VRF_PROGRAM_ID] {
+            svm.add_program(id, &stubs);
+        }
+
+        // the stand-in signs callbacks as the PDA the real VRF program signs with
+        assert_eq!(
+            Pubkey::find_program_address(&[b"identity"], &VRF_PROGRAM_ID).0,
+            VRF_PROGRAM_IDENTITY
+        );
+
+        svm.set_account(
+            MAGIC_CONTEXT_ID,
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![0; 1024],
+                owner: MAGIC_PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        (svm, user)
+    }
+
+    fn user_pdas(user: &Pubkey) -> (Pubkey, Pubkey) {
+        (
+            Pubkey::find_program_address(&[b"user", user.as_ref()], &crate::ID).0,
+            Pubkey::find_program_address(&[b"randomness", user.as_ref()], &crate::ID).0,
+        )
+    }
+
+    fn key(name: &str) -> [u8; KEY_LEN] {
+        let mut key = [0; KEY_LEN];
+        key[..name.len()].copy_from_slice(name.as_bytes());
+        key
+    }
+
+    fn send(svm: &mut LiteSVM, payer: &Keypair, ix: Instruction) -> TransactionResult {
+        let tx = Transaction::new(
+            &[payer],
+            Message::new(&[ix], Some(&payer.pubkey())),
+            svm.latest_blockhash(),
+        );
+        let result = svm.send_transaction(tx);
+        svm.expire_blockhash();
+        result
+    }
+
+    fn assert_er_error(result: TransactionResult, error: ErStateError) {
+        let failed = result.expect_err("transaction should have failed");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(error as u32 + ERROR_CODE_OFFSET)
+            ),
+            "Logs: {}",
+            failed.meta.pretty_logs()
+        );
+    }
+
+    fn fetch<T: AccountDeserialize>(svm: &LiteSVM, address: &Pubkey) -> T {
+        let account = svm.get_account(address).expect("account missing");
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn set_owner(svm: &mut LiteSVM, address: &Pubkey, owner: Pubkey) {
+        let mut account = svm.get_account(address).expect("account missing");
+        account.owner = owner;
+        svm.set_account(*address, account).unwrap();
+    }
+
+    /// What the ER validator does when it clones delegated accounts.
+    fn enter_er(svm: &mut LiteSVM, user: &Pubkey) {
+        let (user_account, pending_randomness) = user_pdas(user);
+        set_owner(svm, &user_account, crate::ID);
+        set_owner(svm, &pending_randomness, crate::ID);
+    }
+
+    fn initialize(svm: &mut LiteSVM, user: &Keypair) {
+        let (user_account, pending_randomness) = user_pdas(&user.pubkey());
+        let ix = Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::InitUser {
+                user: user.pubkey(),
+                user_account,
+                pending_randomness,
+                system_program: anchor_lang::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Initialize {}.data(),
+        };
+        send(svm, user, ix).expect("initialize failed");
+    }
+
+    fn delegate_ix(user: &Pubkey, validator: Pubkey, policy: CommitPolicy) -> Instruction {
+        let (user_account, pending_randomness) = user_pdas(user);
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::Delegate {
+                user: *user,
+                user_account,
+                pending_randomness,
+                validator,
+                system_program: anchor_lang::system_program::ID,
+                buffer_user_account: delegate_buffer_pda_from_delegated_account_and_owner_program(
+                    &user_account,
+                    &crate::ID,
+                ),
+                delegation_record_user_account: delegation_record_pda_from_delegated_account(
+                    &user_account,
+                ),
+                delegation_metadata_user_account: delegation_metadata_pda_from_delegated_account(
+                    &user_account,
+                ),
+                buffer_pending_randomness:
+                    delegate_buffer_pda_from_delegated_account_and_owner_program(
+                        &pending_randomness,
+                        &crate::ID,
+                    ),
+                delegation_record_pending_randomness: delegation_record_pda_from_delegated_account(
+                    &pending_randomness,
+                ),
+                delegation_metadata_pending_randomness:
+                    delegation_metadata_pda_from_delegated_account(&pending_randomness),
+                owner_program: crate::ID,
+                delegation_program: DELEGATION_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Delegate { policy }.data(),
+        }
+    }
+
+    fn update_commit_ix(user: &Pubkey, op: UserOp) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::UpdateCommit {
+                user: *user,
+                user_account: user_pdas(user).0,
+                magic_program: MAGIC_PROGRAM_ID,
+                magic_context: MAGIC_CONTEXT_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::UpdateCommit { op }.data(),
+        }
+    }
+
+    fn update_ix(user: &Pubkey, op: UserOp, with_magic: bool) -> Instruction {
+        let mut accounts = crate::accounts::UpdateUser {
+            user: *user,
+            user_account: user_pdas(user).0,
+        }
+        .to_account_metas(None);
+        if with_magic {
+            accounts.push(AccountMeta::new(MAGIC_CONTEXT_ID, false));
+            accounts.push(AccountMeta::new_readonly(MAGIC_PROGRAM_ID, false));
+        }
+        Instruction {
+            program_id: crate::ID,
+            accounts,
+            data: crate::instruction::Update { op }.data(),
+        }
+    }
+
+    fn undelegate_ix(user: &Pubkey) -> Instruction {
+        let (user_account, pending_randomness) = user_pdas(user);
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::Undelegate {
+                user: *user,
+                user_account,
+                pending_randomness,
+                magic_program: MAGIC_PROGRAM_ID,
+                magic_context: MAGIC_CONTEXT_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Undelegate {}.data(),
+        }
+    }
+
+    fn close_ix(user: &Pubkey) -> Instruction {
+        let (user_account, pending_randomness) = user_pdas(user);
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::CloseUser {
+                user: *user,
+                user_account,
+                pending_randomness,
+                system_program: anchor_lang::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Close {}.data(),
+        }
+    }
+
+    fn vrf_random_er_ix(user: &Pubkey, client_seed: u8, request: RandomRequest) -> Instruction {
+        let (user_account, pending_randomness) = user_pdas(user);
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::VrfRandomEr {
+                user: *user,
+                user_account,
+                pending_randomness,
+                oracle_queue: DEFAULT_EPHEMERAL_QUEUE,
+                program_identity: Pubkey::find_program_address(&[b"identity"], &crate::ID).0,
+                vrf_program: VRF_PROGRAM_ID,
+                slot_hashes: anchor_lang::solana_program::sysvar::slot_hashes::ID,
+                system_program: anchor_lang::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::VrfRandomEr {
+                client_seed,
+                request,
+            }
+            .data(),
+        }
+    }
+
+    /// The oracle answering through the VRF stand-in, which signs as the VRF identity.
+    fn fulfill_ix(user: &Pubkey, randomness: [u8; 32], seed: [u8; 32]) -> Instruction {
+        let (user_account, pending_randomness) = user_pdas(user);
+        let mut data = FULFILL.to_vec();
+        data.extend(crate::instruction::VrfCallback { randomness, seed }.data());
+        Instruction {
+            program_id: VRF_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(VRF_PROGRAM_IDENTITY, false),
+                AccountMeta::new_readonly(crate::ID, false),
+                AccountMeta::new(user_account, false),
+                AccountMeta::new(pending_randomness, false),
+            ],
+            data,
+        }
+    }
+
+    fn set_loot_table_ix(user: &Pubkey, loot: Vec<LootEntry>) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::ConfigureRandom {
+                user: *user,
+                user_account: user_pdas(user).0,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::SetLootTable { loot }.data(),
+        }
+    }
+
+    fn wager_pdas(user: &Pubkey) -> (Pubkey, Pubkey) {
+        (
+            Pubkey::find_program_address(&[b"wager", user.as_ref()], &crate::ID).0,
+            Pubkey::find_program_address(&[b"house"], &crate::ID).0,
+        )
+    }
+
+    fn fund_house_ix(funder: &Pubkey, amount: u64) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::FundHouse {
+                funder: *funder,
+                house: wager_pdas(funder).1,
+                system_program: anchor_lang::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::FundHouse { amount }.data(),
+        }
+    }
+
+    fn place_wager_ix(user: &Pubkey, stake: u64, guess: u8) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::PlaceWager {
+                user: *user,
+                wager: wager_pdas(user).0,
+                system_program: anchor_lang::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::PlaceWager { stake, guess }.data(),
+        }
+    }
+
+    fn roll_wager_ix(user: &Pubkey, client_seed: u8) -> Instruction {
+        let (wager, house) = wager_pdas(user);
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::RollWager {
+                user: *user,
+                wager,
+                house,
+                oracle_queue: DEFAULT_QUEUE,
+                program_identity: Pubkey::find_program_address(&[b"identity"], &crate::ID).0,
+                vrf_program: VRF_PROGRAM_ID,
+                slot_hashes: anchor_lang::solana_program::sysvar::slot_hashes::ID,
+                system_program: anchor_lang::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::RollWager { client_seed }.data(),
+        }
+    }
+
+    /// The oracle answering a roll, with the request id `roll_wager` handed to the queue.
+    fn fulfill_wager_ix(user: &Pubkey, randomness: [u8; 32], request_id: [u8; 32]) -> Instruction {
+        let (wager, house) = wager_pdas(user);
+        let mut data = FULFILL.to_vec();
+        data.extend(
+            crate::instruction::WagerCallback {
+                randomness,
+                request_id,
+            }
+            .data(),
+        );
+        Instruction {
+            program_id: VRF_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(VRF_PROGRAM_IDENTITY, false),
+                AccountMeta::new_readonly(crate::ID, false),
+                AccountMeta::new(wager, false),
+                AccountMeta::new(*user, false),
+                AccountMeta::new(house, false),
+            ],
+            data,
+        }
+    }
+
+    fn refund_wager_ix(user: &Pubkey) -> Instruction {
+        let (wager, house) = wager_pdas(user);
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::RefundWager {
+                user: *user,
+                wager,
+                house,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::RefundWager {}.data(),
+        }
+    }
+
+    /// A funded house and a wager of `stake` on `guess`, rolled and waiting for the oracle.
+    fn setup_rolled_wager(stake: u64, guess: u8) -> (LiteSVM, Keypair, Keypair) {
+        let (mut svm, user) = setup();
+        let oracle = Keypair::new();
+        svm.airdrop(&oracle.pubkey(), 1_000_000_000)
+            .expect("Airdrop failed");
+
+        send(
+            &mut svm,
+            &user,
+            fund_house_ix(&user.pubkey(), 1_000_000_000),
+        )
+        .expect("fund_house failed");
+        send(
+            &mut svm,
+            &user,
+            place_wager_ix(&user.pubkey(), stake, guess),
+        )
+        .expect("place_wager failed");
+        send(&mut svm, &user, roll_wager_ix(&user.pubkey(), 7)).expect("roll_wager failed");
+        (svm, user, oracle)
+    }
+
+    /// Initialized, delegated to `validator` and cloned into the ER.
+    fn setup_delegated(policy: CommitPolicy) -> (LiteSVM, Keypair, Pubkey) {
+        let (mut svm, user) = setup();
+        let validator = Pubkey::new_unique();
+        initialize(&mut svm, &user);
+        send(
+            &mut svm,
+            &user,
+            delegate_ix(&user.pubkey(), validator, policy),
+        )
+        .expect("delegate failed");
+        enter_er(&mut svm, &user.pubkey());
+        (svm, user, validator)
+    }
+
+    fn increment(name: &str) -> UserOp {
+        UserOp::Increment {
+            key: StateKey::Counter { name: key(name) },
+            amount: 1,
+        }
+    }
+
+    fn balance_pda(mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"balance", mint.as_ref(), owner.as_ref()], &crate::ID).0
+    }
+
+    fn vault_pda(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"vault", mint.as_ref()], &crate::ID).0
+    }
+
+    fn deposit_ix(user: &Pubkey, mint: &Pubkey, amount: u64) -> Instruction {
+        let vault = vault_pda(mint);
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::DepositTokens {
+                user: *user,
+                mint: *mint,
+                user_ata: get_associated_token_address(user, mint),
+                vault,
+                vault_ata: get_associated_token_address(&vault, mint),
+                balance: balance_pda(mint, user),
+                associated_token_program: anchor_spl::associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: anchor_lang::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Deposit { amount }.data(),
+        }
+    }
+
+    fn delegate_balance_ix(user: &Pubkey, mint: &Pubkey, validator: Pubkey) -> Instruction {
+        let balance = balance_pda(mint, user);
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::DelegateBalance {
+                user: *user,
+                balance,
+                validator,
+                system_program: anchor_lang::system_program::ID,
+                buffer_balance: delegate_buffer_pda_from_delegated_account_and_owner_program(
+                    &balance,
+                    &crate::ID,
+                ),
+                delegation_record_balance: delegation_record_pda_from_delegated_account(&balance),
+                delegation_metadata_balance: delegation_metadata_pda_from_delegated_account(
+                    &balance,
+                ),
+                owner_program: crate::ID,
+                delegation_program: DELEGATION_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::DelegateBalance {}.data(),
+        }
+    }
+
+    fn transfer_balance_ix(
+        owner: &Pubkey,
+        mint: &Pubkey,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::TransferBalance {
+                owner: *owner,
+                balance: balance_pda(mint, owner),
+                recipient: balance_pda(mint, recipient),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::TransferBalance { amount }.data(),
+        }
+    }
+
+    fn settle_balance_ix(owner: &Pubkey, mint: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::SettleBalance {
+                owner: *owner,
+                balance: balance_pda(mint, owner),
+                magic_program: MAGIC_PROGRAM_ID,
+                magic_context: MAGIC_CONTEXT_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::SettleBalance {}.data(),
+        }
+    }
+
+    fn withdraw_ix(owner: &Pubkey, mint: &Pubkey, amount: u64) -> Instruction {
+        let vault = vault_pda(mint);
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::WithdrawTokens {
+                owner: *owner,
+                mint: *mint,
+                owner_ata: get_associated_token_address(owner, mint),
+                vault,
+                vault_ata: get_associated_token_address(&vault, mint),
+                balance: balance_pda(mint, owner),
+                associated_token_program: anchor_spl::associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: anchor_lang::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Withdraw { amount }.data(),
+        }
+    }
+
+    /// Two users with token accounts of a fresh mint, `alice` holding 1_000.
+    fn setup_tokens() -> (LiteSVM, Keypair, Keypair, Pubkey) {
+        let (mut svm, alice) = setup();
+        let bob = Keypair::new();
+        svm.airdrop(&bob.pubkey(), 10_000_000_000)
+            .expect("Airdrop failed");
+
+        let mint = CreateMint::new(&mut svm, &alice)
+            .decimals(6)
+            .authority(&alice.pubkey())
+            .send()
+            .unwrap();
+        let alice_ata = CreateAssociatedTokenAccount::new(&mut svm, &alice, &mint)
+            .owner(&alice.pubkey())
+            .send()
+            .unwrap();
+        CreateAssociatedTokenAccount::new(&mut svm, &bob, &mint)
+            .owner(&bob.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(&mut svm, &alice, &mint, &alice_ata, 1_000)
+            .send()
+            .unwrap();
+
+        (svm, alice, bob, mint)
+    }
+
+    fn token_amount(svm: &LiteSVM, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        get_spl_account::<spl_token::state::Account>(
+            svm,
+            &get_associated_token_address(owner, mint),
+        )
+        .unwrap()
+        .amount
+    }
+
+    #[test]
+    fn delegate_hands_accounts_to_delegation_program() {
+        let (mut svm, user) = setup();
+        let validator = Pubkey::new_unique();
+        initialize(&mut svm, &user);
+        svm.warp_to_slot(42);
+
+        let policy = CommitPolicy {
+            every_updates: 3,
+            every_slots: 0,
+        };
+        send(
+            &mut svm,
+            &user,
+            delegate_ix(&user.pubkey(), validator, policy),
+        )
+        .expect("delegate failed");
+
+        let (user_account, pending_randomness) = user_pdas(&user.pubkey());
+        for address in [user_account, pending_randomness] {
+            assert_eq!(
+                svm.get_account(&address).unwrap().owner,
+                DELEGATION_PROGRAM_ID
+            );
+        }
+
+        // the delegated data is what the program wrote out before the CPI
+        let state: UserAccount = fetch(&svm, &user_account);
+        assert_eq!(state.commit_policy, policy);
+        assert!(state.delegation.delegated);
+        assert_eq!(state.delegation.validator, validator);
+        assert_eq!(state.delegation.delegated_at_slot, 42);
+        assert_eq!(state.delegation.commit_count, 0);
+    }
+
+    #[test]
+    fn update_commit_applies_and_counts_the_commit() {
+        let (mut svm, user, _) = setup_delegated(CommitPolicy::default());
+
+        send(
+            &mut svm,
+            &user,
+            update_commit_ix(&user.pubkey(), increment("score")),
+        )
+        .expect("update_commit failed");
+        send(
+            &mut svm,
+            &user,
+            update_commit_ix(&user.pubkey(), increment("score")),
+        )
+        .expect("update_commit failed");
+
+        let state: UserAccount = fetch(&svm, &user_pdas(&user.pubkey()).0);
+        assert_eq!(state.counter(&key("score")), Some(2));
+        assert_eq!(state.updates_since_commit, 0);
+        assert_eq!(state.delegation.commit_count, 2);
+    }
+
+    #[test]
+    fn update_commits_once_the_policy_is_due() {
+        let policy = CommitPolicy {
+            every_updates: 2,
+            every_slots: 0,
+        };
+        let (mut svm, user, _) = setup_delegated(policy);
+
+        send(
+            &mut svm,
+            &user,
+            update_ix(&user.pubkey(), increment("score"), false),
+        )
+        .expect("update failed");
+        assert_er_error(
+            send(
+                &mut svm,
+                &user,
+                update_ix(&user.pubkey(), increment("score"), false),
+            ),
+            ErStateError::CommitRequired,
+        );
+        send(
+            &mut svm,
+            &user,
+            update_ix(&user.pubkey(), increment("score"), true),
+        )
+        .expect("update failed");
+
+        let state: UserAccount = fetch(&svm, &user_pdas(&user.pubkey()).0);
+        assert_eq!(state.counter(&key("score")), Some(2));
+        assert_eq!(state.updates_since_commit, 0);
+        assert_eq!(state.delegation.commit_count, 1);
+    }
+
+    #[test]
+    fn undelegate_clears_delegation_and_allows_close() {
+        let policy = CommitPolicy {
+            every_updates: 5,
+            every_slots: 100,
+        };
+        let (mut svm, user, validator) = setup_delegated(policy);
+        send(
+            &mut svm,
+            &user,
+            update_commit_ix(&user.pubkey(), increment("score")),
+        )
+        .expect("update_commit failed");
+
+        // still delegated, closing inside the rollup is refused
+        assert_er_error(
+            send(&mut svm, &user, close_ix(&user.pubkey())),
+            ErStateError::AccountDelegated,
+        );
+
+        // the magic program stand-in leaves the accounts owned by this program,
+        // which is where the undelegate callback hands them back on the base layer
+        send(&mut svm, &user, undelegate_ix(&user.pubkey())).expect("undelegate failed");
+
+        let state: UserAccount = fetch(&svm, &user_pdas(&user.pubkey()).0);
+        assert!(!state.delegation.delegated);
+        assert_eq!(state.delegation.validator, validator);
+        assert_eq!(state.delegation.commit_count, 2);
+        assert_eq!(state.commit_policy, CommitPolicy::default());
+
+        send(&mut svm, &user, close_ix(&user.pubkey())).expect("close failed");
+        let closed = svm.get_account(&user_pdas(&user.pubkey()).0);
+        assert_eq!(closed.map_or(0, |a| a.lamports), 0);
+    }
+
+    #[test]
+    fn vrf_callback_answers_the_pending_request_once() {
+        let (mut svm, user, _) = setup_delegated(CommitPolicy::default());
+        let (user_account, pending_randomness) = user_pdas(&user.pubkey());
+
+        send(
+            &mut svm,
+            &user,
+            vrf_random_er_ix(&user.pubkey(), 7, RandomRequest::Dice),
+        )
+        .expect("vrf_random_er failed");
+        let pending: PendingRandomness = fetch(&svm, &pending_randomness);
+        assert!(pending.pending);
+
+        // a second request waits for the first to be answered
+        assert_er_error(
+            send(
+                &mut svm,
+                &user,
+                vrf_random_er_ix(&user.pubkey(), 8, RandomRequest::Dice),
+            ),
+            ErStateError::RequestPending,
+        );
+
+        // a callback for another seed is rejected, wrapped in the stand-in's CPI
+        let failed = send(
+            &mut svm,
+            &user,
+            fulfill_ix(&user.pubkey(), [1; 32], [0; 32]),
+        )
+        .expect_err("callback with a stale seed should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(ErStateError::RequestMismatch as u32 + ERROR_CODE_OFFSET)
+            )
+        );
+
+        send(
+            &mut svm,
+            &user,
+            fulfill_ix(&user.pubkey(), [1; 32], pending.seed),
+        )
+        .expect("vrf_callback failed");
+
+        let state: UserAccount = fetch(&svm, &user_account);
+        assert!((1..=6).contains(&state.data));
+        assert_eq!(state.random.roll_count, 1);
+        assert_eq!(state.random.rolls[0], state.data);
+        assert!(!fetch::<PendingRandomness>(&svm, &pending_randomness).pending);
+
+        // replaying the answer finds nothing outstanding
+        let failed = send(
+            &mut svm,
+            &user,
+            fulfill_ix(&user.pubkey(), [2; 32], pending.seed),
+        )
+        .expect_err("replayed callback should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(ErStateError::NoPendingRequest as u32 + ERROR_CODE_OFFSET)
+            )
+        );
+    }
+
+    #[test]
+    fn wager_callback_pays_a_correct_guess() {
+        let stake = 10_000_000;
+        let randomness = [3; 32];
+        let roll = ephemeral_vrf_sdk::rnd::random_u8_with_range(&randomness, 1, 6);
+        let (mut svm, user, oracle) = setup_rolled_wager(stake, roll);
+        let (wager, _) = wager_pdas(&user.pubkey());
+
+        let pending: Wager = fetch(&svm, &wager);
+        assert_eq!(pending.status, WagerStatus::Pending);
+
+        // an answer to another request can't settle this roll
+        assert_er_error(
+            send(
+                &mut svm,
+                &oracle,
+                fulfill_wager_ix(&user.pubkey(), randomness, [9; 32]),
+            ),
+            ErStateError::RequestMismatch,
+        );
+
+        let user_before = svm.get_balance(&user.pubkey()).unwrap();
+        send(
+            &mut svm,
+            &oracle,
+            fulfill_wager_ix(&user.pubkey(), randomness, pending.request_id),
+        )
+        .expect("wager_callback failed");
+
+        let won: Wager = fetch(&svm, &wager);
+        assert_eq!(won.status, WagerStatus::Won);
+        assert_eq!(won.roll, roll);
+        assert_eq!(
+            svm.get_balance(&user.pubkey()).unwrap(),
+            user_before + stake * WIN_MULTIPLIER
+        );
+
+        // a duplicate callback finds nothing pending
+        assert_er_error(
+            send(
+                &mut svm,
+                &oracle,
+                fulfill_wager_ix(&user.pubkey(), randomness, pending.request_id),
+            ),
+            ErStateError::WagerNotPending,
+        );
+    }
+
+    #[test]
+    fn wager_callback_sends_a_wrong_guess_to_the_house() {
+        let stake = 10_000_000;
+        let randomness = [3; 32];
+        let roll = ephemeral_vrf_sdk::rnd::random_u8_with_range(&randomness, 1, 6);
+        let (mut svm, user, oracle) = setup_rolled_wager(stake, roll % 6 + 1);
+        let (wager, house) = wager_pdas(&user.pubkey());
+
+        let request_id = fetch::<Wager>(&svm, &wager).request_id;
+        let house_before = svm.get_balance(&house).unwrap();
+        let user_before = svm.get_balance(&user.pubkey()).unwrap();
+        send(
+            &mut svm,
+            &oracle,
+            fulfill_wager_ix(&user.pubkey(), randomness, request_id),
+        )
+        .expect("wager_callback failed");
+
+        assert_eq!(fetch::<Wager>(&svm, &wager).status, WagerStatus::Lost);
+        assert_eq!(
+            svm.get_balance(&house).unwrap(),
+            house_before + stake * WIN_MULTIPLIER
+        );
+        assert_eq!(svm.get_balance(&user.pubkey()).unwrap(), user_before);
+    }
+
+    #[test]
+    fn refund_wager_waits_for_the_callback_timeout() {
+        let stake = 10_000_000;
+        let (mut svm, user, oracle) = setup_rolled_wager(stake, 1);
+        let (wager, house) = wager_pdas(&user.pubkey());
+        let pending: Wager = fetch(&svm, &wager);
+
+        assert_er_error(
+            send(&mut svm, &user, refund_wager_ix(&user.pubkey())),
+            ErStateError::WagerNotExpired,
+        );
+
+        svm.warp_to_slot(pending.requested_slot + WAGER_TIMEOUT_SLOTS);
+        svm.expire_blockhash();
+        let house_before = svm.get_balance(&house).unwrap();
+        let user_before = svm.get_balance(&user.pubkey()).unwrap();
+        send(&mut svm, &user, refund_wager_ix(&user.pubkey())).expect("refund_wager failed");
+
+        // the stake goes back, minus the fee, and the house gets its locked share
+        assert_eq!(fetch::<Wager>(&svm, &wager).status, WagerStatus::Refunded);
+        assert_eq!(
+            svm.get_balance(&house).unwrap(),
+            house_before + stake * (WIN_MULTIPLIER - 1)
+        );
+        assert_eq!(
+            svm.get_balance(&user.pubkey()).unwrap(),
+            user_before + stake - 5_000
+        );
+
+        // the late callback can't settle a refunded wager
+        assert_er_error(
+            send(
+                &mut svm,
+                &oracle,
+                fulfill_wager_ix(&user.pubkey(), [3; 32], pending.request_id),
+            ),
+            ErStateError::WagerNotPending,
+        );
+    }
+
+    #[test]
+    fn vrf_callback_credits_loot_rolls() {
+        let (mut svm, user, _) = setup_delegated(CommitPolicy::default());
+        let (user_account, pending_randomness) = user_pdas(&user.pubkey());
+
+        let loot = vec![
+            LootEntry {
+                item: key("gold"),
+                weight: 9,
+                min: 10,
+                max: 50,
+            },
+            LootEntry {
+                item: key("sword"),
+                weight: 1,
+                min: 1,
+                max: 1,
+            },
+        ];
+        send(&mut svm, &user, set_loot_table_ix(&user.pubkey(), loot))
+            .expect("set_loot_table failed");
+        send(
+            &mut svm,
+            &user,
+            vrf_random_er_ix(&user.pubkey(), 1, RandomRequest::Loot { rolls: 4 }),
+        )
+        .expect("vrf_random_er failed");
+
+        let seed = fetch::<PendingRandomness>(&svm, &pending_randomness).seed;
+        let randomness = [9; 32];
+        send(
+            &mut svm,
+            &user,
+            fulfill_ix(&user.pubkey(), randomness, seed),
+        )
+        .expect("vrf_callback failed");
+
+        // replay the draws off-chain from the same randomness
+        let mut stream = RandomStream::new(randomness);
+        let mut gold = 0;
+        let mut swords = 0;
+        for _ in 0..4 {
+            match stream.weighted_index(&[9, 1]).unwrap() {
+                0 => gold += stream.range_u64(10, 50),
+                _ => swords += stream.range_u64(1, 1),
+            }
+        }
+
+        let state: UserAccount = fetch(&svm, &user_account);
+        assert_eq!(state.random.roll_count, 4);
+        assert_eq!(state.entry(&key("gold")).unwrap_or(0), gold);
+        assert_eq!(state.entry(&key("sword")).unwrap_or(0), swords);
+    }
+
+    #[test]
+    fn random_stream_draws_in_range_and_permutes() {
+        let mut stream = RandomStream::new([3; 32]);
+
+        for _ in 0..256 {
+            assert!((5..=9).contains(&stream.range_u64(5, 9)));
+        }
+        assert_eq!(stream.range_u64(7, 7), 7);
+
+        // zero weights are never drawn
+        for _ in 0..256 {
+            assert_ne!(stream.weighted_index(&[0, 4, 0, 1]), Some(0));
+        }
+        assert_eq!(stream.weighted_index(&[0, 0]), None);
+
+        let mut list: Vec<u8> = (0..16).collect();
+        stream.shuffle(&mut list);
+        let mut sorted = list.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..16).collect::<Vec<u8>>());
+
+        // the same randomness always gives the same draws
+        let mut a = RandomStream::new([5; 32]);
+        let mut b = RandomStream::new([5; 32]);
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn er_balances_settle_back_to_the_vault() {
+        let (mut svm, alice, bob, mint) = setup_tokens();
+        let validator = Pubkey::new_unique();
+
+        send(&mut svm, &alice, deposit_ix(&alice.pubkey(), &mint, 400)).expect("deposit failed");
+        // a zero deposit opens bob's balance so it can receive
+        send(&mut svm, &bob, deposit_ix(&bob.pubkey(), &mint, 0)).expect("deposit failed");
+        assert_eq!(token_amount(&svm, &vault_pda(&mint), &mint), 400);
+
+        for user in [&alice, &bob] {
+            send(
+                &mut svm,
+                user,
+                delegate_balance_ix(&user.pubkey(), &mint, validator),
+            )
+            .expect("delegate_balance failed");
+            let balance = balance_pda(&mint, &user.pubkey());
+            assert_eq!(
+                svm.get_account(&balance).unwrap().owner,
+                DELEGATION_PROGRAM_ID
+            );
+        }
+
+        // while delegated the base layer can't pay the balance out
+        let failed = send(&mut svm, &alice, withdraw_ix(&alice.pubkey(), &mint, 1))
+            .expect_err("withdrawing a delegated balance should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(ErrorCode::AccountOwnedByWrongProgram as u32)
+            )
+        );
+
+        for user in [&alice, &bob] {
+            set_owner(&mut svm, &balance_pda(&mint, &user.pubkey()), crate::ID);
+        }
+        send(
+            &mut svm,
+            &alice,
+            transfer_balance_ix(&alice.pubkey(), &mint, &bob.pubkey(), 150),
+        )
+        .expect("transfer_balance failed");
+        assert_er_error(
+            send(
+                &mut svm,
+                &alice,
+                transfer_balance_ix(&alice.pubkey(), &mint, &bob.pubkey(), 251),
+            ),
+            ErStateError::InsufficientBalance,
+        );
+
+        for user in [&alice, &bob] {
+            send(&mut svm, user, settle_balance_ix(&user.pubkey(), &mint))
+                .expect("settle_balance failed");
+            set_owner(&mut svm, &balance_pda(&mint, &user.pubkey()), crate::ID);
+        }
+
+        send(&mut svm, &bob, withdraw_ix(&bob.pubkey(), &mint, 150)).expect("withdraw failed");
+        send(&mut svm, &alice, withdraw_ix(&alice.pubkey(), &mint, 250)).expect("withdraw failed");
+        assert_er_error(
+            send(&mut svm, &alice, withdraw_ix(&alice.pubkey(), &mint, 1)),
+            ErStateError::InsufficientBalance,
+        );
+
+        assert_eq!(token_amount(&svm, &alice.pubkey(), &mint), 850);
+        assert_eq!(token_amount(&svm, &bob.pubkey(), &mint), 150);
+        assert_eq!(token_amount(&svm, &vault_pda(&mint), &mint), 0);
+        let vault: TokenVault = fetch(&svm, &vault_pda(&mint));
+        assert_eq!(vault.total_balances, 0);
+        for user in [&alice, &bob] {
+            let balance: Balance = fetch(&svm, &balance_pda(&mint, &user.pubkey()));
+            assert_eq!(balance.amount, 0);
+        }
+    }
+
+    #[test]
+    fn transfer_balance_rejects_empty_transfers() {
+        let (mut svm, alice, bob, mint) = setup_tokens();
+        send(&mut svm, &alice, deposit_ix(&alice.pubkey(), &mint, 10)).expect("deposit failed");
+        send(&mut svm, &bob, deposit_ix(&bob.pubkey(), &mint, 0)).expect("deposit failed");
+
+        assert_er_error(
+            send(
+                &mut svm,
+                &alice,
+                transfer_balance_ix(&alice.pubkey(), &mint, &bob.pubkey(), 0),
+            ),
+            ErStateError::InvalidTransfer,
+        );
+    }
+
+    fn session_pda(host: &Pubkey, session_id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"session", host.as_ref(), &session_id.to_le_bytes()],
+            &crate::ID,
+        )
+        .0
+    }
+
+    fn create_session_ix(host: &Pubkey, session_id: u64, max_players: u8) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::CreateSession {
+                host: *host,
+                session: session_pda(host, session_id),
+                system_program: anchor_lang::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::CreateSession {
+                session_id,
+                max_players,
+            }
+            .data(),
+        }
+    }
+
+    fn join_session_ix(player: &Pubkey, session: Pubkey) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::JoinSession {
+                player: *player,
+                session,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::JoinSession {}.data(),
+        }
+    }
+
+    fn delegate_session_ix(host: &Pubkey, session: Pubkey, validator: Pubkey) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::DelegateSession {
+                host: *host,
+                session,
+                validator,
+                system_program: anchor_lang::system_program::ID,
+                buffer_session: delegate_buffer_pda_from_delegated_account_and_owner_program(
+                    &session,
+                    &crate::ID,
+                ),
+                delegation_record_session: delegation_record_pda_from_delegated_account(&session),
+                delegation_metadata_session: delegation_metadata_pda_from_delegated_account(
+                    &session,
+                ),
+                owner_program: crate::ID,
+                delegation_program: DELEGATION_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::DelegateSession {}.data(),
+        }
+    }
+
+    fn make_move_ix(player: &Pubkey, session: Pubkey, points: u64) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::MakeMove {
+                player: *player,
+                session,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::MakeMove { points }.data(),
+        }
+    }
+
+    fn settle_session_ix(player: &Pubkey, session: Pubkey) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::SettleSession {
+                player: *player,
+                session,
+                magic_program: MAGIC_PROGRAM_ID,
+                magic_context: MAGIC_CONTEXT_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::SettleSession {}.data(),
+        }
+    }
+
+    #[test]
+    fn session_settles_without_the_host() {
+        let (mut svm, host) = setup();
+        let guest = Keypair::new();
+        let outsider = Keypair::new();
+        for payer in [&guest, &outsider] {
+            svm.airdrop(&payer.pubkey(), 1_000_000_000)
+                .expect("Airdrop failed");
+        }
+
+        let session = session_pda(&host.pubkey(), 1);
+        send(&mut svm, &host, create_session_ix(&host.pubkey(), 1, 2))
+            .expect("create_session failed");
+        send(&mut svm, &guest, join_session_ix(&guest.pubkey(), session))
+            .expect("join_session failed");
+        send(
+            &mut svm,
+            &host,
+            delegate_session_ix(&host.pubkey(), session, Pubkey::new_unique()),
+        )
+        .expect("delegate_session failed");
+        set_owner(&mut svm, &session, crate::ID);
+
+        for points in [0, MAX_POINTS_PER_MOVE + 1] {
+            assert_er_error(
+                send(
+                    &mut svm,
+                    &guest,
+                    make_move_ix(&guest.pubkey(), session, points),
+                ),
+                ErStateError::InvalidPoints,
+            );
+        }
+        send(
+            &mut svm,
+            &guest,
+            make_move_ix(&guest.pubkey(), session, MAX_POINTS_PER_MOVE),
+        )
+        .expect("make_move failed");
+
+        // the host never comes back, a joined player settles instead
+        assert_er_error(
+            send(
+                &mut svm,
+                &outsider,
+                settle_session_ix(&outsider.pubkey(), session),
+            ),
+            ErStateError::NotAPlayer,
+        );
+        send(
+            &mut svm,
+            &guest,
+            settle_session_ix(&guest.pubkey(), session),
+        )
+        .expect("settle_session failed");
+        set_owner(&mut svm, &session, crate::ID);
+
+        let state: GameSession = fetch(&svm, &session);
+        assert_eq!(state.status, SessionStatus::Settled);
+        assert_eq!(state.players[1].score, MAX_POINTS_PER_MOVE);
+        assert_eq!(state.move_count, 1);
+    }
+}
@@ -0,0 +1,79 @@
+//! Local stand-ins for the programs `er-state-account` calls into, so its LiteSVM
+//! tests run without a validator. The same binary is loaded at the delegation,
+//! magic and VRF program ids:
+//!
+//! - delegation: `Delegate` copies the delegated data back from the buffer, the way
+//!   the delegation program does, leaving the PDA owned by the delegation program
+//! - magic: commits are accepted, the test moves accounts between layers itself
+//! - VRF: requests are accepted, `FULFILL` answers one with test-chosen randomness
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::slice_invoke_signed,
+    entrypoint,
+    instruction::{AccountMeta, Instruction, Seed, Signer},
+    program_error::ProgramError,
+    pubkey::{find_program_address, Pubkey},
+    ProgramResult,
+};
+
+entrypoint!(process_instruction);
+
+/// The delegation program's `Delegate` discriminator.
+pub const DELEGATE: [u8; 8] = [0; 8];
+/// `FULFILL || callback instruction data`, signed by the VRF identity.
+pub const FULFILL: [u8; 8] = *b"fulfill\0";
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    match data.get(..8) {
+        Some(tag) if tag == FULFILL => fulfill(program_id, accounts, &data[8..]),
+        Some(tag) if tag == DELEGATE => delegate(accounts),
+        _ => Ok(()),
+    }
+}
+
+/// `[payer, pda, owner_program, buffer, ..]`, the buffer holding the data `delegate_account` moved out.
+fn delegate(accounts: &[AccountInfo]) -> ProgramResult {
+    let [_payer, pda, _owner_program, buffer, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    pda.try_borrow_mut_data()?
+        .copy_from_slice(&buffer.try_borrow_data()?);
+
+    Ok(())
+}
+
+/// `[identity, callback_program, callback accounts..]`, invoking the callback
+/// with the VRF identity PDA as its first, signing account.
+fn fulfill(program_id: &Pubkey, accounts: &[AccountInfo], callback: &[u8]) -> ProgramResult {
+    let [identity, callback_program, rest @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut metas = vec![AccountMeta::readonly_signer(identity.key())];
+    metas.extend(
+        rest.iter()
+            .map(|a| AccountMeta::new(a.key(), a.is_writable(), false)),
+    );
+    let mut infos = vec![identity];
+    infos.extend(rest);
+
+    let (_, bump) = find_program_address(&[b"identity"], program_id);
+    let bump = [bump];
+    let seeds = [Seed::from(b"identity"), Seed::from(&bump)];
+
+    slice_invoke_signed(
+        &Instruction {
+            program_id: callback_program.key(),
+            data: callback,
+            accounts: &metas,
+        },
+        &infos,
+        &[Signer::from(&seeds)],
+    )
+}
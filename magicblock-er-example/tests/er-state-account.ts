@@ -36,6 +36,13 @@ describe("er-state-account", () => {
 
   const program = anchor.workspace.erStateAccount as Program<ErStateAccount>;
 
+  // counter names and map keys are fixed 16-byte, zero padded
+  const key = (name: string) => {
+    const bytes = Buffer.alloc(16);
+    bytes.write(name);
+    return [...bytes];
+  };
+
   const userAccount = anchor.web3.PublicKey.findProgramAddressSync(
     [Buffer.from("user"), anchor.Wallet.local().publicKey.toBuffer()],
     program.programId
@@ -56,7 +63,7 @@ describe("er-state-account", () => {
 
   it("Update State!", async () => {
     const tx = await program.methods
-      .update(new anchor.BN(42))
+      .update({ set: { key: { data: {} }, value: new anchor.BN(42) } })
       .accountsPartial({
         user: anchor.Wallet.local().publicKey,
         userAccount: userAccount,
@@ -65,9 +72,45 @@ describe("er-state-account", () => {
     console.log("\nUser Account State Updated: ", tx);
   });
 
+  it("Update a named counter and a map entry!", async () => {
+    await program.methods
+      .update({
+        increment: { key: { counter: { name: key("score") } }, amount: new anchor.BN(10) },
+      })
+      .accountsPartial({
+        user: anchor.Wallet.local().publicKey,
+        userAccount: userAccount,
+      })
+      .rpc();
+    const tx = await program.methods
+      .update({ set: { key: { entry: { key: key("level") } }, value: new anchor.BN(3) } })
+      .accountsPartial({
+        user: anchor.Wallet.local().publicKey,
+        userAccount: userAccount,
+      })
+      .rpc();
+
+    const userData = await program.account.userAccount.fetch(userAccount);
+    console.log("Counters: ", userData.counters, "Entries: ", userData.entries);
+    console.log("\nUser Account State Updated: ", tx);
+  });
+
+  it("Resize the map!", async () => {
+    const tx = await program.methods
+      .resize(16)
+      .accountsPartial({
+        user: anchor.Wallet.local().publicKey,
+        userAccount: userAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log("\nUser Account Resized: ", tx);
+  });
+
   it("Delegate to Ephemeral Rollup!", async () => {
+    // commit back to the base layer at least every 5 updates or 50 slots
     let tx = await program.methods
-      .delegate()
+      .delegate({ everyUpdates: 5, everySlots: new anchor.BN(50) })
       .accountsPartial({
         user: anchor.Wallet.local().publicKey,
         userAccount: userAccount,
@@ -81,7 +124,9 @@ describe("er-state-account", () => {
 
   it("Update State and Commit to Base Layer!", async () => {
     let tx = await program.methods
-      .updateCommit(new anchor.BN(43))
+      .updateCommit({
+        increment: { key: { counter: { name: key("score") } }, amount: new anchor.BN(1) },
+      })
       .accountsPartial({
         user: providerEphemeralRollup.wallet.publicKey,
         userAccount: userAccount,
@@ -107,7 +152,7 @@ describe("er-state-account", () => {
 
   it("Update user on ER with a random number via VRF", async () => {
     let tx = await program.methods
-      .vrfRandomEr(2)
+      .vrfRandomEr(2, { dice: {} })
       .accountsPartial({
         user: providerEphemeralRollup.wallet.publicKey,
         userAccount: userAccount,
@@ -162,11 +207,14 @@ describe("er-state-account", () => {
     );
 
     console.log("\nUser Account Undelegated: ", txHash);
+
+    const userData = await program.account.userAccount.fetch(userAccount);
+    console.log("Last delegation: ", userData.delegation);
   });
 
   it("Update State!", async () => {
     let tx = await program.methods
-      .update(new anchor.BN(45))
+      .update({ reset: { key: { counter: { name: key("score") } } } })
       .accountsPartial({
         user: anchor.Wallet.local().publicKey,
         userAccount: userAccount,
@@ -178,7 +226,7 @@ describe("er-state-account", () => {
 
   it("Update Base State with a random number via VRF", async () => {
     let tx = await program.methods
-      .vrfRandomBase(2)
+      .vrfRandomBase(2, { dice: {} })
       .accountsPartial({
         user: anchor.Wallet.local().publicKey,
         userAccount: userAccount,
@@ -191,6 +239,160 @@ describe("er-state-account", () => {
     console.log("\nUser Account State Updated with a random on Base: ", tx);
   });
 
+  it("Configure random tables", async () => {
+    await program.methods
+      .setWeights([60, 30, 10])
+      .accountsPartial({ user: anchor.Wallet.local().publicKey, userAccount })
+      .rpc();
+    await program.methods
+      .setList(Buffer.from([1, 2, 3, 4, 5, 6, 7, 8]))
+      .accountsPartial({ user: anchor.Wallet.local().publicKey, userAccount })
+      .rpc();
+    const tx = await program.methods
+      .setLootTable([
+        { item: key("gold"), weight: 90, min: 10, max: 50 },
+        { item: key("sword"), weight: 10, min: 1, max: 1 },
+      ])
+      .accountsPartial({ user: anchor.Wallet.local().publicKey, userAccount })
+      .rpc();
+
+    console.log("\nRandom tables configured: ", tx);
+  });
+
+  it("Roll the loot table via VRF", async () => {
+    const pendingRandomness = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("randomness"), anchor.Wallet.local().publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    // one request at a time, wait for the dice callback to land
+    for (let i = 0; i < 30; i++) {
+      const pending = await program.account.pendingRandomness.fetch(
+        pendingRandomness
+      );
+      if (!pending.pending) break;
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+    }
+
+    const tx = await program.methods
+      .vrfRandomBase(3, { loot: { rolls: 3 } })
+      .accountsPartial({
+        user: anchor.Wallet.local().publicKey,
+        userAccount: userAccount,
+      })
+      .rpc();
+
+    console.log("\nLoot roll requested: ", tx);
+  });
+
+  describe("Multiplayer session", () => {
+    const host = anchor.Wallet.local().publicKey;
+    const player2 = anchor.web3.Keypair.generate();
+    const sessionId = new anchor.BN(Date.now());
+    const session = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("session"), host.toBuffer(), sessionId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+    // ER transactions are paid by the local wallet, co-signed by `signers`
+    const sendEr = async (tx: anchor.web3.Transaction, signers: anchor.web3.Keypair[]) => {
+      tx.feePayer = providerEphemeralRollup.wallet.publicKey;
+      tx.recentBlockhash = (
+        await providerEphemeralRollup.connection.getLatestBlockhash()
+      ).blockhash;
+      tx = await providerEphemeralRollup.wallet.signTransaction(tx);
+      return providerEphemeralRollup.sendAndConfirm(tx, signers);
+    };
+
+    it("Create and join a session!", async () => {
+      await program.methods
+        .createSession(sessionId, 2)
+        .accountsPartial({ host, session })
+        .rpc();
+      const tx = await program.methods
+        .joinSession()
+        .accountsPartial({ player: player2.publicKey, session })
+        .signers([player2])
+        .rpc();
+      console.log("\nSession joined: ", tx);
+    });
+
+    it("Delegate the session to Ephemeral Rollup!", async () => {
+      const tx = await program.methods
+        .delegateSession()
+        .accountsPartial({
+          host,
+          session,
+          validator: new PublicKey("MAS1Dt9qreoRMQ14YQuhg8UTZMMzDdKhmkZMECCzk57"),
+        })
+        .rpc({ skipPreflight: true });
+      console.log("\nSession delegated: ", tx);
+    });
+
+    it("Both players move on the ER!", async () => {
+      for (let i = 0; i < 5; i++) {
+        await sendEr(
+          await program.methods
+            .makeMove(new anchor.BN(10))
+            .accountsPartial({ player: host, session })
+            .transaction(),
+          []
+        );
+        await sendEr(
+          await program.methods
+            .makeMove(new anchor.BN(7))
+            .accountsPartial({ player: player2.publicKey, session })
+            .transaction(),
+          [player2]
+        );
+      }
+    });
+
+    it("Settle the session back to base layer!", async () => {
+      const txHash = await sendEr(
+        await program.methods
+          .settleSession()
+          .accountsPartial({ player: host, session })
+          .transaction(),
+        []
+      );
+      await GetCommitmentSignature(txHash, providerEphemeralRollup.connection);
+
+      const state = await program.account.gameSession.fetch(session);
+      console.log("\nFinal scores: ", state.players.map((p) => p.score.toString()));
+    });
+  });
+
+  describe("Dice wager", () => {
+    const user = anchor.Wallet.local().publicKey;
+
+    it("Fund the house and place a wager!", async () => {
+      await program.methods
+        .fundHouse(new anchor.BN(LAMPORTS_PER_SOL / 10))
+        .accountsPartial({ funder: user })
+        .rpc();
+      const tx = await program.methods
+        .placeWager(new anchor.BN(LAMPORTS_PER_SOL / 100), 4)
+        .accountsPartial({ user })
+        .rpc();
+      console.log("\nWager placed: ", tx);
+    });
+
+    it("Roll the dice via VRF", async () => {
+      const tx = await program.methods.rollWager(7).accountsPartial({ user }).rpc();
+
+      const wager = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("wager"), user.toBuffer()],
+        program.programId
+      )[0];
+      // the callback lands in a later transaction
+      await new Promise((resolve) => setTimeout(resolve, 5000));
+      const state = await program.account.wager.fetch(wager);
+      console.log("\nWager status: ", state.status, "roll: ", state.roll);
+      console.log("Wager rolled: ", tx);
+    });
+  });
+
   it("Close Account!", async () => {
     const tx = await program.methods
       .close()
@@ -0,0 +1,98 @@
+#![no_std]
+
+//! Typed, borrow-checked access to Pinocchio program accounts.
+//!
+//! A state struct implements [`PinocchioAccount`] once and gets owner, length,
+//! discriminator and alignment checks on every load, instead of each program
+//! hand-rolling `unsafe` casts over the account data.
+
+use bytemuck::Pod;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    instruction::Signer,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+pub trait PinocchioAccount: Pod {
+    /// Program that owns accounts of this type.
+    const OWNER: Pubkey;
+
+    /// Tag stored in front of the struct and verified on every load, so an
+    /// account of another type with the same owner and size is rejected.
+    /// Empty for untagged layouts; the programs here use Anchor's
+    /// `sha256("account:<Name>")[..8]`.
+    const DISCRIMINATOR: &'static [u8] = &[];
+
+    /// Full account size: discriminator followed by the struct.
+    const LEN: usize = Self::DISCRIMINATOR.len() + core::mem::size_of::<Self>();
+
+    /// Owner and length checks, without borrowing the data.
+    fn check(account: &AccountInfo) -> ProgramResult {
+        if account.owner() != &Self::OWNER {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if account.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
+
+    /// Borrows the account data as `Self`.
+    fn load(account: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        Self::check(account)?;
+
+        let data = account.try_borrow_data()?;
+        let (discriminator, state) = data.split_at(Self::DISCRIMINATOR.len());
+        if discriminator != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        bytemuck::try_from_bytes::<Self>(state).map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Ref::map(data, |data| {
+            bytemuck::from_bytes(&data[Self::DISCRIMINATOR.len()..])
+        }))
+    }
+
+    /// Mutably borrows the account data as `Self`.
+    fn load_mut(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        Self::check(account)?;
+
+        let mut data = account.try_borrow_mut_data()?;
+        let (discriminator, state) = data.split_at_mut(Self::DISCRIMINATOR.len());
+        if discriminator != Self::DISCRIMINATOR {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        bytemuck::try_from_bytes_mut::<Self>(state)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(RefMut::map(data, |data| {
+            bytemuck::from_bytes_mut(&mut data[Self::DISCRIMINATOR.len()..])
+        }))
+    }
+
+    /// Creates the account rent exempt, paid by `payer` and signed by `signers`
+    /// (the PDA seeds), writes the discriminator and hands back the zeroed state.
+    fn init<'a>(
+        account: &'a AccountInfo,
+        payer: &AccountInfo,
+        signers: &[Signer],
+    ) -> Result<RefMut<'a, Self>, ProgramError> {
+        CreateAccount {
+            from: payer,
+            to: account,
+            owner: &Self::OWNER,
+            space: Self::LEN as u64,
+            lamports: Rent::get()?.minimum_balance(Self::LEN),
+        }
+        .invoke_signed(signers)?;
+
+        account.try_borrow_mut_data()?[..Self::DISCRIMINATOR.len()]
+            .copy_from_slice(Self::DISCRIMINATOR);
+
+        Self::load_mut(account)
+    }
+}